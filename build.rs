@@ -0,0 +1,41 @@
+use std::{env, fs, path::Path};
+
+/// Finds the `src/dayN.rs` modules and writes the registry of their `DayN`
+/// solutions, sorted by day, together with a test checking the examples of
+/// each of them.
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut days = fs::read_dir("src")
+        .expect("cannot read the src directory")
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_prefix("day")?
+                .strip_suffix(".rs")?
+                .parse::<u32>()
+                .ok()
+        })
+        .collect::<Vec<u32>>();
+    days.sort();
+
+    let entries = days
+        .iter()
+        .map(|day| format!("        &crate::day{day}::Day{day},\n"))
+        .collect::<String>();
+    let registry = format!(
+        "/// Every day found in `src/`, sorted by day.\n\
+         pub fn solutions() -> Vec<&'static dyn Puzzle> {{\n    vec![\n{entries}    ]\n}}\n"
+    );
+
+    let tests = days
+        .iter()
+        .map(|day| format!("#[test]\nfn day{day}() {{\n    super::check_examples({day});\n}}\n"))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("registry.rs"), registry)
+        .expect("cannot write the registry");
+    fs::write(Path::new(&out_dir).join("example_tests.rs"), tests)
+        .expect("cannot write the example tests");
+}
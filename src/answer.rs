@@ -0,0 +1,156 @@
+use std::fmt;
+
+/// Answer of a part, telling the runner what kind of value it is so that
+/// each output format can show it properly.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// Rows of a bitmap drawn by the puzzle, e.g. the CRT of day10.
+    Grid(Vec<String>),
+    /// Placeholder of a part that is not solved yet.
+    Unimplemented,
+}
+
+const UNIMPLEMENTED: &str = "not implemented";
+
+impl Answer {
+    /// Splits a drawing into the rows of a grid, the blank lines around it
+    /// are dropped.
+    pub fn grid(text: &str) -> Answer {
+        let rows = text.trim_matches('\n').lines().map(|row| row.to_string());
+        Answer::Grid(rows.collect())
+    }
+
+    /// Guesses the kind of an answer stored as text, e.g. in the answers file.
+    pub fn parse(text: &str) -> Answer {
+        let trimmed = text.trim();
+        if trimmed == UNIMPLEMENTED {
+            Answer::Unimplemented
+        } else if let Ok(number) = trimmed.parse::<i64>() {
+            Answer::Int(number)
+        } else if trimmed.contains('\n') {
+            Answer::grid(text)
+        } else {
+            Answer::Text(trimmed.to_string())
+        }
+    }
+
+    pub fn is_implemented(&self) -> bool {
+        !matches!(self, Answer::Unimplemented)
+    }
+
+    /// The answer without the whitespace that does not matter: around a
+    /// text, at the end of the rows of a grid and the blank rows around it.
+    fn normalized(&self) -> String {
+        match self {
+            Answer::Int(number) => number.to_string(),
+            Answer::Text(text) => text.trim().to_string(),
+            Answer::Grid(rows) => {
+                let rows = rows.iter().map(|row| row.trim_end()).collect::<Vec<&str>>();
+                let first = rows.iter().position(|row| !row.is_empty());
+                let last = rows.iter().rposition(|row| !row.is_empty());
+                match (first, last) {
+                    (Some(first), Some(last)) => rows[first..=last].join("\n"),
+                    _ => String::new(),
+                }
+            }
+            Answer::Unimplemented => UNIMPLEMENTED.to_string(),
+        }
+    }
+}
+
+/// Answers of different kinds are equal when they read the same, so that a
+/// number is equal to the same number stored as text.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        match (self, other) {
+            (Answer::Unimplemented, Answer::Unimplemented) => true,
+            (Answer::Unimplemented, _) | (_, Answer::Unimplemented) => false,
+            _ => self.normalized() == other.normalized(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+            Answer::Unimplemented => write!(f, "{UNIMPLEMENTED}"),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($int:ty),*) => {
+        $(impl From<$int> for Answer {
+            fn from(number: $int) -> Answer {
+                match i64::try_from(number) {
+                    Ok(number) => Answer::Int(number),
+                    Err(_) => Answer::Text(number.to_string()),
+                }
+            }
+        })*
+    };
+}
+
+from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert!(matches!(Answer::parse("71924"), Answer::Int(71924)));
+        assert!(matches!(Answer::parse(" -3\n"), Answer::Int(-3)));
+        assert!(matches!(Answer::parse("CMZ"), Answer::Text(text) if text == "CMZ"));
+        assert!(matches!(
+            Answer::parse("not implemented"),
+            Answer::Unimplemented
+        ));
+        match Answer::parse("\n#..#\n.##.\n") {
+            Answer::Grid(rows) => assert_eq!(rows, vec!["#..#", ".##."]),
+            other => panic!("expected a grid, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_equality() {
+        assert_eq!(Answer::from(95437u64), Answer::from("95437"));
+        assert_eq!(Answer::from("CMZ"), Answer::Text(" CMZ\n".to_string()));
+        assert_ne!(Answer::from("CMZ"), Answer::from("MCD"));
+        assert_eq!(
+            Answer::grid("\n#..#\n.##.\n"),
+            Answer::Grid(vec![
+                "#..#  ".to_string(),
+                ".##.".to_string(),
+                String::new()
+            ])
+        );
+        assert_ne!(Answer::grid("#..#\n.##."), Answer::grid("#..#\n.#.."));
+        assert_eq!(Answer::Unimplemented, Answer::parse("not implemented"));
+        assert_ne!(Answer::Unimplemented, Answer::from("not implemented"));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(-12).to_string(), "-12");
+        assert_eq!(Answer::grid("\n#.\n.#\n").to_string(), "#.\n.#");
+        assert_eq!(Answer::Unimplemented.to_string(), "not implemented");
+    }
+}
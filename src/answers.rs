@@ -0,0 +1,248 @@
+use std::{collections::HashMap, error::Error, fmt, fs, io};
+
+use crate::answer::Answer;
+use crate::fixtures;
+
+pub const DEFAULT_PATH: &str = "data/answers.txt";
+
+#[derive(Debug, PartialEq)]
+pub struct AnswersError(String);
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid answers file: {}", self.0)
+    }
+}
+
+impl Error for AnswersError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail,
+    Missing,
+    NotImplemented,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match self {
+            Status::Pass => "PASS",
+            Status::Fail => "FAIL",
+            Status::Missing => "MISSING",
+            Status::NotImplemented => "NOT-IMPLEMENTED",
+        };
+        write!(f, "{status}")
+    }
+}
+
+/// Escapes an answer so that it fits on a single line of the answers file.
+pub fn escape(answer: &str) -> String {
+    answer
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> Result<String, AnswersError> {
+    let mut result = String::new();
+    let mut chars = answer.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => result.push('\\'),
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            other => {
+                return Err(AnswersError(format!(
+                    "unknown escape sequence '\\{}'",
+                    other.map_or(String::new(), String::from)
+                )))
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Known answers keyed by day, part and the input file they were computed
+/// from. Each line of the file holds one tab separated entry:
+///
+/// ```text
+/// <day>\t<part>\t<input path>\t<escaped answer>
+/// ```
+///
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: HashMap<(u32, u32, String), String>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers, AnswersError> {
+        let mut answers = Answers::default();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.splitn(4, '\t').collect::<Vec<&str>>()[..] {
+                [day, part, input, answer] => {
+                    let (day, part) = match (day.parse::<u32>(), part.parse::<u32>()) {
+                        (Ok(day), Ok(part)) => (day, part),
+                        _ => {
+                            return Err(AnswersError(format!(
+                                "line {}: day and part must be numbers",
+                                idx + 1
+                            )))
+                        }
+                    };
+                    answers.insert(day, part, input, &unescape(answer)?);
+                }
+                _ => {
+                    return Err(AnswersError(format!(
+                        "line {}: expected 4 tab separated fields",
+                        idx + 1
+                    )))
+                }
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Loads the answers file. A default answers file that does not exist yet
+    /// has no answers, any other path must be readable so that a mistyped
+    /// `--answers` is not taken for an empty file.
+    pub fn load(path: &str) -> Result<Answers, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text),
+            Err(err)
+                if err.kind() == io::ErrorKind::NotFound
+                    && [DEFAULT_PATH, fixtures::ANSWERS].contains(&path) =>
+            {
+                Ok(Answers::default())
+            }
+            Err(err) => Err(AnswersError(format!("cannot read {path}: {err}"))),
+        }
+    }
+
+    pub fn get(&self, day: u32, part: u32, input: &str) -> Option<&str> {
+        self.entries
+            .get(&(day, part, input.to_string()))
+            .map(|answer| answer.as_str())
+    }
+
+    /// Part, input path and answer of every entry of `day`, sorted.
+    pub fn entries(&self, day: u32) -> Vec<(u32, &str, &str)> {
+        let mut entries = self
+            .entries
+            .iter()
+            .filter(|((number, _, _), _)| *number == day)
+            .map(|((_, part, input), answer)| (*part, input.as_str(), answer.as_str()))
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
+
+    pub fn insert(&mut self, day: u32, part: u32, input: &str, answer: &str) {
+        self.entries
+            .insert((day, part, input.to_string()), answer.to_string());
+    }
+
+    /// Compares `actual` with the known answer, both read as [`Answer`]s so
+    /// that insignificant whitespace does not matter.
+    pub fn check(&self, day: u32, part: u32, input: &str, actual: &Answer) -> Status {
+        if !actual.is_implemented() {
+            return Status::NotImplemented;
+        }
+        match self.get(day, part, input) {
+            None => Status::Missing,
+            Some(expected) if Answer::parse(expected) == *actual => Status::Pass,
+            Some(_) => Status::Fail,
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut keys = self.entries.keys().collect::<Vec<_>>();
+        keys.sort();
+        for key in keys {
+            let (day, part, input) = key;
+            writeln!(f, "{day}\t{part}\t{input}\t{}", escape(&self.entries[key]))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_check() {
+        let text = "# known answers\n\
+                    1\t1\tdata/day1.txt\t71924\n\
+                    \n\
+                    10\t2\tdata/day10.txt\t\\n#..#\\n.##.\\n\n";
+        let answers = Answers::parse(text).unwrap();
+        assert_eq!(answers.get(1, 1, "data/day1.txt"), Some("71924"));
+        assert_eq!(answers.get(10, 2, "data/day10.txt"), Some("\n#..#\n.##.\n"));
+        assert_eq!(answers.get(1, 2, "data/day1.txt"), None);
+        assert_eq!(answers.entries(1), vec![(1, "data/day1.txt", "71924")]);
+        assert!(answers.entries(2).is_empty());
+
+        let check = |day, part, input, actual| answers.check(day, part, input, &actual);
+        assert_eq!(
+            check(1, 1, "data/day1.txt", Answer::Int(71924)),
+            Status::Pass
+        );
+        assert_eq!(check(1, 1, "data/day1.txt", Answer::Int(1)), Status::Fail);
+        assert_eq!(
+            check(1, 1, "other.txt", Answer::Int(71924)),
+            Status::Missing
+        );
+        assert_eq!(
+            check(10, 2, "data/day10.txt", Answer::grid("#..#  \n.##.")),
+            Status::Pass
+        );
+        assert_eq!(
+            check(11, 2, "data/day11.txt", Answer::Unimplemented),
+            Status::NotImplemented
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut answers = Answers::default();
+        answers.insert(10, 2, "data/day10.txt", "\n#\t\\.\n");
+        answers.insert(2, 1, "data/day2.txt", "12645");
+        let text = answers.to_string();
+        assert_eq!(
+            text,
+            "2\t1\tdata/day2.txt\t12645\n10\t2\tdata/day10.txt\t\\n#\\t\\\\.\\n\n"
+        );
+        let parsed = Answers::parse(&text).unwrap();
+        assert_eq!(parsed.get(10, 2, "data/day10.txt"), Some("\n#\t\\.\n"));
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        assert!(Answers::parse("1\t1\tdata/day1.txt").is_err());
+        assert!(Answers::parse("one\t1\tdata/day1.txt\t5").is_err());
+        assert!(Answers::parse("1\t1\tdata/day1.txt\t\\x").is_err());
+    }
+
+    #[test]
+    fn test_load() {
+        assert!(Answers::load(DEFAULT_PATH).is_ok());
+        let err = Answers::load("data/answres.txt").unwrap_err();
+        assert!(
+            err.to_string().contains("cannot read data/answres.txt"),
+            "{err}"
+        );
+        // a directory exists but cannot be read as a file
+        assert!(Answers::load("data").is_err());
+    }
+}
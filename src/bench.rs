@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        if samples.is_empty() {
+            return Stats {
+                min: Duration::ZERO,
+                median: Duration::ZERO,
+                mean: Duration::ZERO,
+                p95: Duration::ZERO,
+            };
+        }
+        samples.sort();
+        let len = samples.len();
+        let median = if len.is_multiple_of(2) {
+            (samples[len / 2 - 1] + samples[len / 2]) / 2
+        } else {
+            samples[len / 2]
+        };
+        // nearest-rank percentile, i.e. the smallest sample that is greater
+        // or equal to 95 % of all the samples
+        let p95 = samples[(len * 95).div_ceil(100) - 1];
+        Stats {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / len as u32,
+            p95,
+        }
+    }
+}
+
+/// Calls `fun` `warmup` times without measuring anything, then `iterations`
+/// more times while recording the wall time of each call.
+pub fn measure(warmup: u32, iterations: u32, mut fun: impl FnMut()) -> Stats {
+    (0..warmup).for_each(|_| fun());
+    let samples = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            fun();
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(samples)
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{nanos}ns")
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(millis(&[5, 1, 4, 2, 3]));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+
+        let stats = Stats::from_samples(millis(&(1..=100).collect::<Vec<u64>>()));
+        assert_eq!(stats.median, Duration::from_micros(50_500));
+        assert_eq!(stats.p95, Duration::from_millis(95));
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let stats = measure(2, 5, || calls += 1);
+        assert_eq!(calls, 7);
+        assert!(stats.min <= stats.p95);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(512)), "512ns");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_millis(2500)), "2.50s");
+    }
+}
@@ -0,0 +1,821 @@
+use std::{error::Error, fmt, time::Duration};
+
+use crate::answers;
+use crate::fixtures;
+use crate::history;
+use crate::output::Format;
+use crate::watch;
+
+pub const USAGE: &str = "\
+usage: advent-of-code [run] <day|all> [--part <1|2>] [--input <path>|--example]
+                     [--timeout <secs>] [--format <format>] [--mem] [--stats]
+       advent-of-code bench <day|all> [--part <1|2>] [--warmup <n>] [--iterations <n>]
+                     [--label <label>] [--history <path>] [--scale <n>]
+                     [--compare [--baseline <label>] [--threshold <percent>]]
+       advent-of-code verify <day|all> [--part <1|2>] [--example] [--answers <path>]
+       advent-of-code watch <day> [--part <1|2>] [--input <path>|--example]
+                     [--answers <path>] [--interval <secs>]
+       advent-of-code report <day|all> [--input <path>|--example] [--answers <path>]
+                     [--output <path>]
+       advent-of-code new <day>
+
+commands:
+  run <day|all>       solve the given day (or every registered day) on each of
+                      its inputs
+  bench <day|all>     time repeated runs of the given day (build with --release)
+                      and add the results to the benchmark history
+  verify <day|all>    check the answers of the given day against the answers file
+  watch <day>         solve the given day again whenever its inputs or the answers
+                      file change, showing what changed since the previous run
+  report <day|all>    write a Markdown table of the answers of the given day (or
+                      every registered day) on its main input
+  new <day>           create the module and the input file of a new day
+
+options:
+  -p, --part <1|2>    solve only the given part
+  -i, --input <path>  read the puzzle input from <path> instead of data/day<N>.txt
+                      and data/day<N>/*.txt, use - to read it from the standard
+                      input
+  -e, --example       solve the example fixtures/day<N>.txt instead of the input
+  -t, --timeout <secs>
+                      give up on a part after <secs> seconds, 0 waits forever
+                      (default 60)
+  --mem               count the allocations, the bytes allocated and the peak
+                      of live bytes of each part of run
+  --stats             show figures about the inputs of the days that have some,
+                      after their answers (text format only)
+  -f, --format <text|json|csv>
+                      output format of run (default text), a JSON answer is a
+                      number, a string, an array of rows, or null when the part
+                      failed or is not implemented, JSON and CSV tell the two
+                      apart with an implemented field
+  --warmup <n>        unmeasured runs before benchmarking (default 1)
+  -n, --iterations <n>
+                      measured runs per part (default 10)
+  --label <label>     label of the benchmark results in the history (default the
+                      git commit, with -dirty when there are uncommitted changes)
+  --history <path>    benchmark history file (default data/bench-history.txt)
+  --scale <n>         also benchmark an input <n> times larger, for the days that
+                      can grow their input, and show how much slower each stage got
+  --compare           report the stages that got slower than their latest result
+                      in the history
+  --baseline <label>  compare with the latest results labelled <label> instead
+  --threshold <percent>
+                      slowdown allowed by --compare (default 10)
+  --interval <secs>   how often watch looks for changes (default 0.5)
+  -o, --output <path> file the report is written to (default the standard output)
+  --answers <path>    answers file used by verify, watch and report (default data/answers.txt, or
+                      fixtures/answers.txt together with --example)
+  -h, --help          print this message";
+
+#[derive(Debug, PartialEq)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Days {
+    All,
+    Single(u32),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunOptions {
+    pub days: Days,
+    pub part: Option<u32>,
+    pub input: Option<String>,
+    pub example: bool,
+    pub timeout: Option<Duration>,
+    pub memory: bool,
+    /// Whether `run` shows the figures of [`crate::solution::Solution::stats`].
+    pub stats: bool,
+}
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Compares the results of `bench` with the latest ones recorded under
+/// `baseline`, or under any label when there is none.
+#[derive(Debug, PartialEq)]
+pub struct CompareOptions {
+    pub baseline: Option<String>,
+    /// Percentage by which a stage may get slower before it is reported.
+    pub threshold: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub run: RunOptions,
+    pub warmup: u32,
+    pub iterations: u32,
+    /// Label of the results in the history, the git commit when `None`.
+    pub label: Option<String>,
+    pub history: String,
+    pub compare: Option<CompareOptions>,
+    /// Factor by which the inputs are grown for a second benchmark.
+    pub scale: Option<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VerifyOptions {
+    pub run: RunOptions,
+    pub answers: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WatchOptions {
+    pub run: RunOptions,
+    pub answers: String,
+    pub interval: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReportOptions {
+    pub run: RunOptions,
+    pub answers: String,
+    /// File the report is written to, the standard output when `None`.
+    pub output: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions, Format),
+    Bench(BenchOptions),
+    Verify(VerifyOptions),
+    Watch(WatchOptions),
+    Report(ReportOptions),
+    New(u32),
+    Help,
+}
+
+fn parse_days(arg: &str) -> Result<Days, UsageError> {
+    if arg == "all" {
+        return Ok(Days::All);
+    }
+    match arg.strip_prefix("day").unwrap_or(arg).parse::<u32>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(Days::Single(day)),
+        _ => Err(UsageError(format!(
+            "invalid day '{arg}', expected a number from 1 to 25 or 'all'"
+        ))),
+    }
+}
+
+fn parse_part(arg: &str) -> Result<u32, UsageError> {
+    match arg {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(UsageError(format!("invalid part '{arg}', expected 1 or 2"))),
+    }
+}
+
+fn parse_count(flag: &str, arg: &str) -> Result<u32, UsageError> {
+    arg.parse::<u32>()
+        .map_err(|_| UsageError(format!("invalid value '{arg}' for '{flag}'")))
+}
+
+fn parse_timeout(arg: &str) -> Result<Option<Duration>, UsageError> {
+    match arg.parse::<f64>() {
+        Ok(0.0) => Ok(None),
+        Ok(secs) if secs.is_finite() && secs > 0.0 => Ok(Some(Duration::from_secs_f64(secs))),
+        _ => Err(UsageError(format!(
+            "invalid timeout '{arg}', expected a number of seconds"
+        ))),
+    }
+}
+
+fn expect_value(flag: &str, value: Option<String>) -> Result<String, UsageError> {
+    value.ok_or_else(|| UsageError(format!("missing value for '{flag}'")))
+}
+
+/// Parses the options shared by all the commands, the values of `extra` flags
+/// are returned as they are so that each command can interpret them, and the
+/// `switches` that take no value are returned with an empty one.
+fn parse_run(
+    args: Vec<String>,
+    extra: &[&str],
+    switches: &[&str],
+) -> Result<(RunOptions, Vec<(String, String)>), UsageError> {
+    let mut days = None;
+    let mut part = None;
+    let mut input = None;
+    let mut example = false;
+    let mut timeout = Some(DEFAULT_TIMEOUT);
+    let mut memory = false;
+    let mut stats = false;
+    let mut extra_values = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-p" | "--part" => part = Some(parse_part(&expect_value(&arg, args.next())?)?),
+            "-i" | "--input" => input = Some(expect_value(&arg, args.next())?),
+            "-e" | "--example" => example = true,
+            "-t" | "--timeout" => timeout = parse_timeout(&expect_value(&arg, args.next())?)?,
+            "--mem" => memory = true,
+            "--stats" => stats = true,
+            flag if extra.contains(&flag) => {
+                let value = expect_value(flag, args.next())?;
+                extra_values.push((arg, value));
+            }
+            flag if switches.contains(&flag) => extra_values.push((arg, String::new())),
+            flag if flag.starts_with('-') => {
+                return Err(UsageError(format!("unknown option '{flag}'")))
+            }
+            _ if days.is_some() => return Err(UsageError(format!("unexpected argument '{arg}'"))),
+            _ => days = Some(parse_days(&arg)?),
+        }
+    }
+
+    let days = days.unwrap_or(Days::All);
+    if days == Days::All && input.is_some() {
+        return Err(UsageError(
+            "'--input' can only be used together with a single day".to_string(),
+        ));
+    }
+    if example && input.is_some() {
+        return Err(UsageError(
+            "'--input' and '--example' cannot be used together".to_string(),
+        ));
+    }
+    let options = RunOptions {
+        days,
+        part,
+        input,
+        example,
+        timeout,
+        memory,
+        stats,
+    };
+    Ok((options, extra_values))
+}
+
+/// Memory is only counted by `run`, the other commands report timings that
+/// counting would slow down. Statistics are only shown by `run` too.
+fn reject_run_only(run: &RunOptions) -> Result<(), UsageError> {
+    match (run.memory, run.stats) {
+        (true, _) => Err(UsageError("'--mem' is only supported by run".to_string())),
+        (_, true) => Err(UsageError("'--stats' is only supported by run".to_string())),
+        _ => Ok(()),
+    }
+}
+
+fn parse_label(flag: &str, arg: &str) -> Result<String, UsageError> {
+    match arg.is_empty() || arg.contains(char::is_whitespace) {
+        true => Err(UsageError(format!(
+            "invalid label '{arg}' for '{flag}', expected a word without spaces"
+        ))),
+        false => Ok(arg.to_string()),
+    }
+}
+
+fn parse_threshold(arg: &str) -> Result<f64, UsageError> {
+    match arg.trim_end_matches('%').parse::<f64>() {
+        Ok(percent) if percent.is_finite() && percent >= 0.0 => Ok(percent),
+        _ => Err(UsageError(format!(
+            "invalid threshold '{arg}', expected a percentage"
+        ))),
+    }
+}
+
+fn parse_bench(args: Vec<String>) -> Result<BenchOptions, UsageError> {
+    let (run, extra) = parse_run(
+        args,
+        &[
+            "--warmup",
+            "-n",
+            "--iterations",
+            "--label",
+            "--history",
+            "--baseline",
+            "--threshold",
+            "--scale",
+        ],
+        &["--compare"],
+    )?;
+    reject_run_only(&run)?;
+    let mut options = BenchOptions {
+        run,
+        warmup: 1,
+        iterations: 10,
+        label: None,
+        history: history::DEFAULT_PATH.to_string(),
+        compare: None,
+        scale: None,
+    };
+    let (mut compare, mut baseline, mut threshold) = (false, None, None);
+    for (flag, value) in extra {
+        match flag.as_str() {
+            "--warmup" => options.warmup = parse_count(&flag, &value)?,
+            "--label" => options.label = Some(parse_label(&flag, &value)?),
+            "--history" => options.history = value,
+            "--compare" => compare = true,
+            "--baseline" => baseline = Some(parse_label(&flag, &value)?),
+            "--threshold" => threshold = Some(parse_threshold(&value)?),
+            "--scale" => match parse_count(&flag, &value)? {
+                factor @ 2.. => options.scale = Some(factor),
+                _ => return Err(UsageError("'--scale' must be at least 2".to_string())),
+            },
+            _ => options.iterations = parse_count(&flag, &value)?,
+        }
+    }
+    if options.iterations == 0 {
+        return Err(UsageError("'--iterations' must be at least 1".to_string()));
+    }
+    if compare {
+        options.compare = Some(CompareOptions {
+            baseline,
+            threshold: threshold.unwrap_or(history::DEFAULT_THRESHOLD),
+        });
+    } else if baseline.is_some() || threshold.is_some() {
+        return Err(UsageError(
+            "'--baseline' and '--threshold' can only be used together with '--compare'".to_string(),
+        ));
+    }
+    Ok(options)
+}
+
+fn parse_format(arg: &str) -> Result<Format, UsageError> {
+    match arg {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(UsageError(format!(
+            "invalid format '{arg}', expected text, json or csv"
+        ))),
+    }
+}
+
+fn parse_run_command(args: Vec<String>) -> Result<Command, UsageError> {
+    let (run, extra) = parse_run(args, &["-f", "--format"], &[])?;
+    let format = match extra.into_iter().next_back() {
+        Some((_, format)) => parse_format(&format)?,
+        None => Format::Text,
+    };
+    // statistics are free text, they would break a structured document
+    if run.stats && format != Format::Text {
+        return Err(UsageError(
+            "'--stats' can only be used with the text format".to_string(),
+        ));
+    }
+    Ok(Command::Run(run, format))
+}
+
+fn default_answers(run: &RunOptions) -> String {
+    if run.example {
+        fixtures::ANSWERS.to_string()
+    } else {
+        answers::DEFAULT_PATH.to_string()
+    }
+}
+
+fn parse_verify(args: Vec<String>) -> Result<VerifyOptions, UsageError> {
+    let (run, extra) = parse_run(args, &["--answers"], &[])?;
+    reject_run_only(&run)?;
+    let answers = extra
+        .into_iter()
+        .next_back()
+        .map_or(default_answers(&run), |(_, path)| path);
+    Ok(VerifyOptions { run, answers })
+}
+
+fn parse_watch(args: Vec<String>) -> Result<WatchOptions, UsageError> {
+    let (run, extra) = parse_run(args, &["--answers", "--interval"], &[])?;
+    reject_run_only(&run)?;
+    if run.days == Days::All {
+        return Err(UsageError("'watch' expects a single day".to_string()));
+    }
+    if run.input.as_deref() == Some("-") {
+        return Err(UsageError(
+            "'watch' cannot watch the standard input".to_string(),
+        ));
+    }
+    let mut options = WatchOptions {
+        answers: default_answers(&run),
+        run,
+        interval: watch::DEFAULT_INTERVAL,
+    };
+    for (flag, value) in extra {
+        match flag.as_str() {
+            "--answers" => options.answers = value,
+            _ => match parse_timeout(&value) {
+                Ok(Some(interval)) => options.interval = interval,
+                _ => {
+                    return Err(UsageError(format!(
+                        "invalid interval '{value}', expected a positive number of seconds"
+                    )))
+                }
+            },
+        }
+    }
+    Ok(options)
+}
+
+fn parse_report(args: Vec<String>) -> Result<ReportOptions, UsageError> {
+    let (run, extra) = parse_run(args, &["--answers", "-o", "--output"], &[])?;
+    reject_run_only(&run)?;
+    if run.part.is_some() {
+        return Err(UsageError("'report' always solves both parts".to_string()));
+    }
+    let mut options = ReportOptions {
+        answers: default_answers(&run),
+        run,
+        output: None,
+    };
+    for (flag, value) in extra {
+        match flag.as_str() {
+            "--answers" => options.answers = value,
+            _ => options.output = Some(value),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_new(args: Vec<String>) -> Result<Command, UsageError> {
+    match &args[..] {
+        [day] if !day.starts_with('-') => match parse_days(day)? {
+            Days::Single(day) => Ok(Command::New(day)),
+            Days::All => Err(UsageError("'new' expects a single day".to_string())),
+        },
+        _ => Err(UsageError("'new' expects exactly one day".to_string())),
+    }
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, UsageError> {
+    let mut args = args.into_iter().collect::<Vec<String>>();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(Command::Help);
+    }
+    match args.first().map(|arg| arg.as_str()) {
+        Some("help") => Ok(Command::Help),
+        Some("run") => {
+            args.remove(0);
+            parse_run_command(args)
+        }
+        Some("bench") => {
+            args.remove(0);
+            Ok(Command::Bench(parse_bench(args)?))
+        }
+        Some("verify") => {
+            args.remove(0);
+            Ok(Command::Verify(parse_verify(args)?))
+        }
+        Some("watch") => {
+            args.remove(0);
+            Ok(Command::Watch(parse_watch(args)?))
+        }
+        Some("report") => {
+            args.remove(0);
+            Ok(Command::Report(parse_report(args)?))
+        }
+        Some("new") => {
+            args.remove(0);
+            parse_new(args)
+        }
+        _ => parse_run_command(args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Command, UsageError> {
+        parse_args(line.split_whitespace().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_run_defaults_to_all_days() {
+        let expected = Command::Run(
+            RunOptions {
+                days: Days::All,
+                part: None,
+                input: None,
+                example: false,
+                timeout: Some(DEFAULT_TIMEOUT),
+                memory: false,
+                stats: false,
+            },
+            Format::Text,
+        );
+        assert_eq!(parse(""), Ok(expected));
+        assert_eq!(
+            parse("run all"),
+            Ok(Command::Run(
+                RunOptions {
+                    days: Days::All,
+                    part: None,
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                Format::Text,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_run_single_day() {
+        assert_eq!(
+            parse("run 7 --part 2 --input some/file.txt --format json"),
+            Ok(Command::Run(
+                RunOptions {
+                    days: Days::Single(7),
+                    part: Some(2),
+                    input: Some("some/file.txt".to_string()),
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                Format::Json,
+            ))
+        );
+        assert_eq!(
+            parse("day12 -p 1 -f csv"),
+            Ok(Command::Run(
+                RunOptions {
+                    days: Days::Single(12),
+                    part: Some(1),
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                Format::Csv,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_usage_errors() {
+        assert!(parse("run 26").is_err());
+        assert!(parse("run seven").is_err());
+        assert_eq!(
+            parse("run dayday7"),
+            Err(UsageError(
+                "invalid day 'dayday7', expected a number from 1 to 25 or 'all'".to_string()
+            ))
+        );
+        assert!(parse("run 7 --part 3").is_err());
+        assert!(parse("run 7 --part").is_err());
+        assert!(parse("run 7 --verbose").is_err());
+        assert!(parse("run 7 8").is_err());
+        assert!(parse("run all --input some/file.txt").is_err());
+        assert_eq!(parse("run 7 --help"), Ok(Command::Help));
+        assert!(parse("run 7 --iterations 5").is_err());
+        assert!(parse("run 7 --format xml").is_err());
+        assert!(parse("run 7 --timeout soon").is_err());
+        assert!(parse("run 7 --timeout -1").is_err());
+        assert!(parse("bench 7 --format json").is_err());
+        assert!(parse("bench 7 --iterations 0").is_err());
+        assert!(parse("bench 7 --warmup many").is_err());
+        assert!(parse("verify 7 --answers").is_err());
+        assert!(parse("run 7 --example --input some/file.txt").is_err());
+        assert!(parse("bench 7 --mem").is_err());
+        assert!(parse("verify 7 --mem").is_err());
+        assert!(parse("watch 7 --mem").is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(
+            parse("verify --answers other.txt"),
+            Ok(Command::Verify(VerifyOptions {
+                run: RunOptions {
+                    days: Days::All,
+                    part: None,
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                answers: "other.txt".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse("verify 3 -p 1"),
+            Ok(Command::Verify(VerifyOptions {
+                run: RunOptions {
+                    days: Days::Single(3),
+                    part: Some(1),
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                answers: "data/answers.txt".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_example() {
+        match parse("run all --example") {
+            Ok(Command::Run(options, _)) => assert!(options.example && options.input.is_none()),
+            other => panic!("expected the run command, got {other:?}"),
+        }
+        match parse("verify 5 -e") {
+            Ok(Command::Verify(options)) => assert_eq!(options.answers, "fixtures/answers.txt"),
+            other => panic!("expected the verify command, got {other:?}"),
+        }
+        match parse("verify 5 -e --answers other.txt") {
+            Ok(Command::Verify(options)) => assert_eq!(options.answers, "other.txt"),
+            other => panic!("expected the verify command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_watch() {
+        assert_eq!(
+            parse("watch 11 -p 1 --interval 2"),
+            Ok(Command::Watch(WatchOptions {
+                run: RunOptions {
+                    days: Days::Single(11),
+                    part: Some(1),
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                answers: "data/answers.txt".to_string(),
+                interval: Duration::from_secs(2),
+            }))
+        );
+        match parse("watch 11 --example") {
+            Ok(Command::Watch(options)) => {
+                assert_eq!(options.answers, "fixtures/answers.txt");
+                assert_eq!(options.interval, watch::DEFAULT_INTERVAL);
+            }
+            other => panic!("expected the watch command, got {other:?}"),
+        }
+        assert!(parse("watch").is_err());
+        assert!(parse("watch all").is_err());
+        assert!(parse("watch 11 --interval 0").is_err());
+        assert!(parse("watch 11 --format json").is_err());
+        assert!(parse("watch 11 --input -").is_err());
+    }
+
+    #[test]
+    fn test_memory() {
+        match parse("run 9 --mem --format csv") {
+            Ok(Command::Run(options, Format::Csv)) => assert!(options.memory),
+            other => panic!("expected the run command, got {other:?}"),
+        }
+        match parse("run 9") {
+            Ok(Command::Run(options, _)) => assert!(!options.memory),
+            other => panic!("expected the run command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        match parse("run 1 --stats --example") {
+            Ok(Command::Run(options, Format::Text)) => assert!(options.stats && options.example),
+            other => panic!("expected the run command, got {other:?}"),
+        }
+        assert!(parse("run 1 --stats --format json").is_err());
+        assert!(parse("bench 1 --stats").is_err());
+        assert!(parse("report 1 --stats").is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        assert_eq!(
+            parse("report -o RESULTS.md"),
+            Ok(Command::Report(ReportOptions {
+                run: RunOptions {
+                    days: Days::All,
+                    part: None,
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                answers: "data/answers.txt".to_string(),
+                output: Some("RESULTS.md".to_string()),
+            }))
+        );
+        match parse("report 10 --example") {
+            Ok(Command::Report(options)) => {
+                assert_eq!(options.answers, "fixtures/answers.txt");
+                assert_eq!(options.output, None);
+            }
+            other => panic!("expected the report command, got {other:?}"),
+        }
+        assert!(parse("report 10 --part 1").is_err());
+        assert!(parse("report 10 --format json").is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(parse("new 13"), Ok(Command::New(13)));
+        assert_eq!(parse("new day14"), Ok(Command::New(14)));
+        assert!(parse("new").is_err());
+        assert!(parse("new all").is_err());
+        assert!(parse("new 13 14").is_err());
+        assert!(parse("new 13 --part 1").is_err());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let timeout = |line: &str| match parse(line) {
+            Ok(Command::Run(options, _)) => options.timeout,
+            _ => panic!("expected the run command"),
+        };
+        assert_eq!(timeout("run 12"), Some(DEFAULT_TIMEOUT));
+        assert_eq!(timeout("run 12 -t 0"), None);
+        assert_eq!(
+            timeout("run 12 --timeout 2.5"),
+            Some(Duration::from_millis(2500))
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        assert_eq!(
+            parse("bench 12 --warmup 0 -n 50"),
+            Ok(Command::Bench(BenchOptions {
+                run: RunOptions {
+                    days: Days::Single(12),
+                    part: None,
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                warmup: 0,
+                iterations: 50,
+                label: None,
+                history: "data/bench-history.txt".to_string(),
+                compare: None,
+                scale: None,
+            }))
+        );
+        assert_eq!(
+            parse("bench"),
+            Ok(Command::Bench(BenchOptions {
+                run: RunOptions {
+                    days: Days::All,
+                    part: None,
+                    input: None,
+                    example: false,
+                    timeout: Some(DEFAULT_TIMEOUT),
+                    memory: false,
+                    stats: false,
+                },
+                warmup: 1,
+                iterations: 10,
+                label: None,
+                history: "data/bench-history.txt".to_string(),
+                compare: None,
+                scale: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_compare() {
+        let bench = |line: &str| match parse(line) {
+            Ok(Command::Bench(options)) => options,
+            other => panic!("expected the bench command, got {other:?}"),
+        };
+        let options = bench("bench 5 --label regex-once --compare");
+        assert_eq!(options.label.as_deref(), Some("regex-once"));
+        assert_eq!(
+            options.compare,
+            Some(CompareOptions {
+                baseline: None,
+                threshold: history::DEFAULT_THRESHOLD,
+            })
+        );
+        let options = bench("bench 5 --compare --baseline abc123 --threshold 25% --history h.txt");
+        assert_eq!(options.history, "h.txt");
+        assert_eq!(
+            options.compare,
+            Some(CompareOptions {
+                baseline: Some("abc123".to_string()),
+                threshold: 25.0,
+            })
+        );
+        assert!(parse("bench 5 --threshold 25").is_err());
+        assert!(parse("bench 5 --compare --threshold -5").is_err());
+        assert!(parse_label("--label", "two words").is_err());
+        assert!(parse("run 5 --compare").is_err());
+        assert_eq!(bench("bench 5 --scale 1000").scale, Some(1000));
+        assert!(parse("bench 5 --scale 1").is_err());
+        assert!(parse("run 5 --scale 1000").is_err());
+    }
+}
@@ -1,14 +1,133 @@
-use std::fs;
+use std::{
+    error::Error,
+    fs,
+    io::{self, Read},
+};
 
-pub(crate) fn read_file_split_by(path: String, split_by: &str) -> Vec<String> {
-    fs::read_to_string(path)
-        .unwrap()
-        .trim()
-        .split(split_by)
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>()
+use crate::answer::Answer;
+use crate::error::{Result, SolveError};
+
+mod geometry;
+mod grid;
+pub mod interval;
+pub mod parser;
+pub mod search;
+
+pub use geometry::{Direction, Point, Vec2};
+pub use grid::{Grid, Offset, Pos, NEIGHBOURS4, NEIGHBOURS8};
+
+/// Reads the whole puzzle input from `path`, `-` stands for the standard input.
+pub fn read_input(path: &str) -> io::Result<String> {
+    match path {
+        "-" => read_from(io::stdin().lock()),
+        _ => fs::read_to_string(path),
+    }
+}
+
+pub fn read_from(mut reader: impl Read) -> io::Result<String> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Solves a part of a day with the input taken from any reader, e.g. a
+/// network buffer or `std::io::stdin()`.
+pub fn solve_from(
+    reader: impl Read,
+    solve: fn(&str) -> Result<Answer>,
+) -> std::result::Result<Answer, Box<dyn Error>> {
+    Ok(solve(&read_from(reader)?)?)
+}
+
+pub(crate) fn read_file(day: u32, path: String) -> Result<String> {
+    read_input(&path).map_err(|err| SolveError::new(day, format!("cannot read {path}: {err}")))
 }
 
-pub(crate) fn read_file(path: String) -> Vec<String> {
-    read_file_split_by(path, "\n")
+/// Splits the trimmed input, the parts borrow from `input`.
+pub(crate) fn split_input<'a>(input: &'a str, split_by: &str) -> Vec<&'a str> {
+    input.trim().split(split_by).collect()
+}
+
+/// Lines of the trimmed input, without a byte order mark or the `'\r'` of
+/// CRLF line endings, so that they never end up in a parse error.
+pub(crate) fn lines(input: &str) -> Vec<&str> {
+    split_input(strip_bom(input), "\n")
+        .into_iter()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect()
+}
+
+/// The input without its byte order mark, when it starts with one.
+pub fn strip_bom(input: &str) -> &str {
+    input.strip_prefix('\u{feff}').unwrap_or(input)
+}
+
+/// Splits the input into lines without trimming it, so significant leading
+/// whitespace survives. Only a byte order mark, CRLF line endings and the
+/// trailing newlines are normalised away. Days that do not go through
+/// [`parser::parse_all`] use it to keep their leading whitespace.
+pub fn raw_lines(input: &str) -> Vec<&str> {
+    let input = strip_bom(input).trim_end_matches(['\n', '\r']);
+    if input.is_empty() {
+        return Vec::new();
+    }
+    input
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect()
+}
+
+/// The trimmed input written `factor` times, joined by `separator`.
+pub(crate) fn repeat_input(input: &str, separator: &str, factor: usize) -> String {
+    vec![input.trim(); factor].join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day1;
+
+    #[test]
+    fn test_split_input() {
+        assert_eq!(lines("a\nb\n\nc\n"), vec!["a", "b", "", "c"]);
+        assert_eq!(
+            lines("\u{feff}a\r\nb\r\n\r\nc\r\n"),
+            vec!["a", "b", "", "c"]
+        );
+        assert_eq!(split_input("a\nb\n\nc\n", "\n\n"), vec!["a\nb", "c"]);
+        assert_eq!(repeat_input("a\nb\n", "\n\n", 2), "a\nb\n\na\nb");
+    }
+
+    #[test]
+    fn test_raw_lines() {
+        assert_eq!(
+            raw_lines("\u{feff}    [D]    \r\n[N] [C]    \r\n\r\n"),
+            vec!["    [D]    ", "[N] [C]    "]
+        );
+        assert_eq!(raw_lines("  a\n\n b \n"), vec!["  a", "", " b "]);
+        assert!(raw_lines("\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_solve_from() {
+        let reader = "1000\n2000\n\n4000\n".as_bytes();
+        assert_eq!(
+            solve_from(reader, day1::solve_part1).unwrap(),
+            Answer::Int(4000)
+        );
+        let reader = "1000\nlots\n".as_bytes();
+        assert_eq!(
+            solve_from(reader, day1::solve_part1)
+                .unwrap_err()
+                .to_string(),
+            "day1, line 2: invalid number of calories 'lots'"
+        );
+    }
+
+    #[test]
+    fn test_read_file() {
+        let err = read_file(3, "data/missing.txt".to_string()).unwrap_err();
+        assert_eq!(err.day, 3);
+        assert!(err.message.starts_with("cannot read data/missing.txt"));
+    }
 }
@@ -0,0 +1,230 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// Position on an unbounded plane, `y` grows downwards like the rows of the
+/// puzzle maps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// Displacement between two [`Point`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    /// Number of steps between the points moving along the axes only.
+    pub fn manhattan(self, other: Point) -> i64 {
+        (other - self).manhattan()
+    }
+
+    /// Number of steps between the points when diagonal moves are allowed.
+    pub fn chebyshev(self, other: Point) -> i64 {
+        (other - self).chebyshev()
+    }
+}
+
+impl Vec2 {
+    pub const fn new(x: i64, y: i64) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    pub fn manhattan(self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn chebyshev(self) -> i64 {
+        self.x.abs().max(self.y.abs())
+    }
+
+    /// The sign of each coordinate, i.e. a step of at most one cell along
+    /// each axis towards the same direction.
+    pub fn signum(self) -> Vec2 {
+        Vec2::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl Add<Vec2> for Point {
+    type Output = Point;
+
+    fn add(self, delta: Vec2) -> Point {
+        Point::new(self.x + delta.x, self.y + delta.y)
+    }
+}
+
+impl AddAssign<Vec2> for Point {
+    fn add_assign(&mut self, delta: Vec2) {
+        *self = *self + delta;
+    }
+}
+
+impl Sub<Vec2> for Point {
+    type Output = Point;
+
+    fn sub(self, delta: Vec2) -> Point {
+        Point::new(self.x - delta.x, self.y - delta.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vec2;
+
+    fn sub(self, other: Point) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, factor: i64) -> Vec2 {
+        Vec2::new(self.x * factor, self.y * factor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting from the top.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Reads a direction written as `U`/`R`/`D`/`L`, `N`/`E`/`S`/`W` or as
+    /// one of the arrows `^`/`>`/`v`/`<`.
+    pub fn parse(text: &str) -> Option<Direction> {
+        match text {
+            "U" | "N" | "^" => Some(Direction::Up),
+            "R" | "E" | ">" => Some(Direction::Right),
+            "D" | "S" | "v" => Some(Direction::Down),
+            "L" | "W" | "<" => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    /// Reads a direction written only as `U`/`R`/`D`/`L`, for the inputs in
+    /// which any other notation is a mistake.
+    pub fn from_udlr(text: &str) -> Option<Direction> {
+        match text {
+            "U" | "R" | "D" | "L" => Direction::parse(text),
+            _ => None,
+        }
+    }
+
+    pub fn delta(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::new(0, -1),
+            Direction::Right => Vec2::new(1, 0),
+            Direction::Down => Vec2::new(0, 1),
+            Direction::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    /// Quarter turn clockwise.
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    /// Quarter turn counterclockwise.
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Point::new(1, 2), Point::new(4, -2));
+        assert_eq!(b - a, Vec2::new(3, -4));
+        assert_eq!(a + (b - a), b);
+        assert_eq!(b - Vec2::new(4, -2), Point::ORIGIN);
+        assert_eq!(-Vec2::new(3, -4) * 2, Vec2::new(-6, 8));
+        assert_eq!(
+            Vec2::new(1, 1) + Vec2::new(2, 0) - Vec2::new(0, 1),
+            Vec2::new(3, 0)
+        );
+
+        let mut c = a;
+        c += Vec2::new(-1, -2);
+        assert_eq!(c, Point::ORIGIN);
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point::new(1, 2), Point::new(4, -2));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(Vec2::new(-5, 0).signum(), Vec2::new(-1, 0));
+        assert_eq!(Vec2::new(2, -7).signum(), Vec2::new(1, -1));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::parse("U"), Some(Direction::Up));
+        assert_eq!(Direction::parse("<"), Some(Direction::Left));
+        assert_eq!(Direction::parse("X"), None);
+        assert_eq!(Direction::from_udlr("D"), Some(Direction::Down));
+        assert_eq!(Direction::from_udlr("S"), None);
+        assert_eq!(Direction::from_udlr("^"), None);
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction::Down.delta(), Vec2::new(0, 1));
+        for direction in Direction::ALL {
+            assert_eq!(
+                direction.delta() + direction.opposite().delta(),
+                Vec2::default()
+            );
+            assert_eq!(direction.turn_right().turn_left(), direction);
+        }
+    }
+}
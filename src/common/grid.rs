@@ -0,0 +1,329 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use super::geometry::{Direction, Point};
+use crate::error::{Result, SolveError};
+
+/// Position in a grid as `(row, column)`, the top left cell is `(0, 0)`.
+pub type Pos = (usize, usize);
+
+/// Step between two positions as `(rows, columns)`.
+pub type Offset = (isize, isize);
+
+/// Up, right, down and left.
+pub const NEIGHBOURS4: [Offset; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// The four neighbours sharing a side followed by the four diagonal ones.
+pub const NEIGHBOURS8: [Offset; 8] = [
+    (-1, 0),
+    (0, 1),
+    (1, 0),
+    (0, -1),
+    (-1, 1),
+    (1, 1),
+    (1, -1),
+    (-1, -1),
+];
+
+/// Rectangular grid of cells stored row after row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Rows become columns, the cell at `(i, j)` moves to `(j, i)`.
+    pub fn transpose(&self) -> Grid<T> {
+        let cells = (0..self.width)
+            .flat_map(|j| (0..self.height).map(move |i| (i, j)))
+            .map(|pos| self[pos].clone());
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: cells.collect(),
+        }
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    pub fn rotate(&self) -> Grid<T> {
+        let cells = (0..self.width)
+            .flat_map(|j| (0..self.height).rev().map(move |i| (i, j)))
+            .map(|pos| self[pos].clone());
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: cells.collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from rows that must all have the same length, the row
+    /// that does not is reported on its line.
+    pub fn from_rows(day: u32, rows: Vec<Vec<T>>) -> Result<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (idx, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(SolveError::new(day, "Rows of different length").at_line(idx + 1));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a character map, one row per line, turning each character into
+    /// a cell with `map`. Errors of `map` are reported on the line of the
+    /// character. Only the line breaks ending the input are dropped, spaces
+    /// are cells like any other character.
+    pub fn parse(
+        day: u32,
+        input: &str,
+        mut map: impl FnMut(Pos, char) -> Result<T>,
+    ) -> Result<Grid<T>> {
+        let rows = input
+            .trim_end_matches(['\n', '\r'])
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(j, ch)| map((i, j), ch).map_err(|err| err.at_line(i + 1)))
+                    .collect::<Result<Vec<T>>>()
+            })
+            .collect::<Result<Vec<Vec<T>>>>()?;
+        Grid::from_rows(day, rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (i, j): Pos) -> Option<&T> {
+        (i < self.height && j < self.width).then(|| &self.cells[i * self.width + j])
+    }
+
+    pub fn get_mut(&mut self, (i, j): Pos) -> Option<&mut T> {
+        (i < self.height && j < self.width).then(|| &mut self.cells[i * self.width + j])
+    }
+
+    /// Position `offset` away from `pos`, if it is still inside the grid.
+    pub fn offset(&self, (i, j): Pos, (di, dj): Offset) -> Option<Pos> {
+        let i = i.checked_add_signed(di)?;
+        let j = j.checked_add_signed(dj)?;
+        (i < self.height && j < self.width).then_some((i, j))
+    }
+
+    /// Position one step towards `direction`, if it is still inside the grid.
+    pub fn step(&self, pos: Pos, direction: Direction) -> Option<Pos> {
+        let delta = direction.delta();
+        self.offset(pos, (delta.y as isize, delta.x as isize))
+    }
+
+    /// Grid position of `point`, taking `x` as the column and `y` as the row.
+    pub fn pos_of(&self, point: Point) -> Option<Pos> {
+        let i = usize::try_from(point.y).ok()?;
+        let j = usize::try_from(point.x).ok()?;
+        (i < self.height && j < self.width).then_some((i, j))
+    }
+
+    /// Cell `offset` away from `pos`, `None` outside of the grid.
+    pub fn get_offset(&self, pos: Pos, offset: Offset) -> Option<&T> {
+        self.offset(pos, offset).and_then(|pos| self.get(pos))
+    }
+
+    /// Every position, row after row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// Every cell with its position, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, i: usize) -> impl Iterator<Item = &T> {
+        self.cells[i * self.width..(i + 1) * self.width].iter()
+    }
+
+    /// Panics when `j` is not a column of the grid, like [`Grid::row`] does
+    /// for rows.
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        assert!(
+            j < self.width,
+            "no column {j} in a grid {} wide",
+            self.width
+        );
+        self.cells.iter().skip(j).step_by(self.width)
+    }
+
+    /// The positions reached by stepping `offset` from `pos` again and again
+    /// until leaving the grid, `pos` itself excluded.
+    pub fn ray(&self, pos: Pos, offset: Offset) -> impl Iterator<Item = Pos> + '_ {
+        let mut current = pos;
+        std::iter::from_fn(move || {
+            current = self.offset(current, offset)?;
+            Some(current)
+        })
+    }
+
+    /// The neighbours of `pos` inside the grid that share a side with it.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS4
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    /// The neighbours of `pos` inside the grid, diagonals included.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS8
+            .iter()
+            .filter_map(move |&offset| self.offset(pos, offset))
+    }
+
+    pub fn map<U>(&self, fun: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(fun).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+/// One line per row, the cells of a row are written next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.height {
+            for cell in self.row(i) {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>> {
+        Grid::parse(8, input, |_, ch| {
+            ch.to_digit(10)
+                .ok_or_else(|| SolveError::new(8, format!("Unknown digit '{ch}'")))
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        assert_eq!(
+            digits("12\n4x\n"),
+            Err(SolveError::new(8, "Unknown digit 'x'").at_line(2))
+        );
+        assert_eq!(
+            digits("12\n456\n"),
+            Err(SolveError::new(8, "Rows of different length").at_line(2))
+        );
+
+        // a first row starting with spaces keeps them
+        let map = Grid::parse(8, "  #\r\n# #\r\n\r\n", |_, ch| Ok(ch)).unwrap();
+        assert_eq!(map.to_string(), "  #\n# #\n");
+        assert_eq!(map[(0, 2)], '#');
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        let values =
+            |positions: Vec<Pos>| positions.iter().map(|&pos| grid[pos]).collect::<Vec<u32>>();
+        assert_eq!(values(grid.neighbours4((0, 0)).collect()), vec![2, 4]);
+        assert_eq!(values(grid.neighbours4((1, 1)).collect()), vec![2, 6, 8, 4]);
+        assert_eq!(values(grid.neighbours8((2, 2)).collect()), vec![6, 8, 5]);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_offsets_and_rays() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (2, 1)), Some((2, 1)));
+        assert_eq!(grid.get_offset((1, 1), (1, 1)), Some(&9));
+        assert_eq!(grid.get_offset((1, 1), (0, 2)), None);
+        assert_eq!(
+            grid.ray((2, 0), (-1, 1)).collect::<Vec<Pos>>(),
+            vec![(1, 1), (0, 2)]
+        );
+        assert_eq!(grid.ray((0, 0), (0, -1)).count(), 0);
+        assert_eq!(grid.step((0, 1), Direction::Down), Some((1, 1)));
+        assert_eq!(grid.step((0, 1), Direction::Up), None);
+        assert_eq!(grid.pos_of(Point::new(2, 1)), Some((1, 2)));
+        assert_eq!(grid.pos_of(Point::new(-1, 1)), None);
+        assert_eq!(grid.pos_of(Point::new(1, 3)), None);
+        assert_eq!(grid.row(1).copied().collect::<Vec<u32>>(), vec![4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<u32>>(), vec![3, 6, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "no column 3 in a grid 3 wide")]
+    fn test_column_outside() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        grid.column(3).for_each(drop);
+    }
+
+    #[test]
+    fn test_transpose_and_rotate() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!(grid.transpose().to_string(), "14\n25\n36\n");
+        assert_eq!(grid.rotate().to_string(), "41\n52\n63\n");
+        assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+        assert!(grid.map(|&n| n % 2 == 0)[(0, 1)]);
+
+        let mut grid = Grid::new(2, 1, '.');
+        grid[(0, 1)] = '#';
+        assert_eq!(grid.to_string(), ".#\n");
+    }
+}
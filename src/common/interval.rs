@@ -0,0 +1,357 @@
+//! Spans of consecutive integers and sets of them, without ever expanding
+//! a span into its values.
+
+use std::{fmt, ops::RangeInclusive};
+
+/// Integer types intervals can be made of.
+pub trait Integer: Copy + Ord + fmt::Debug {
+    /// The next integer, `None` past the largest one.
+    fn next(self) -> Option<Self>;
+
+    /// The previous integer, `None` before the smallest one.
+    fn prev(self) -> Option<Self>;
+
+    /// Number of integers from `self` to `other`, `other` excluded.
+    fn distance(self, other: Self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($int:ty => $unsigned:ty),+) => {
+        $(
+            impl Integer for $int {
+                fn next(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn prev(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn distance(self, other: Self) -> u128 {
+                    other.wrapping_sub(self) as $unsigned as u128
+                }
+            }
+        )+
+    };
+}
+
+impl_integer!(
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize,
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize
+);
+
+/// Non-empty span of consecutive integers, both ends included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Integer> Interval<T> {
+    /// The integers from `start` to `end`, both included. `None` when `end`
+    /// comes before `start`.
+    pub fn closed(start: T, end: T) -> Option<Interval<T>> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    /// The integers from `start` up to `end` excluded, `None` when there are
+    /// none.
+    pub fn half_open(start: T, end: T) -> Option<Interval<T>> {
+        Interval::closed(start, end.prev()?)
+    }
+
+    pub fn single(value: T) -> Interval<T> {
+        Interval {
+            start: value,
+            end: value,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    /// The last integer of the interval.
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// Number of integers in the interval, it saturates for a `u128` or
+    /// `i128` interval spanning the whole type.
+    #[allow(clippy::len_without_is_empty)] // intervals are never empty
+    pub fn len(&self) -> u128 {
+        self.start.distance(self.end).saturating_add(1)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Whether every integer of `other` is in the interval.
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// Whether the intervals overlap or `other` starts right after the
+    /// interval ends, or the other way around.
+    pub fn touches(&self, other: &Interval<T>) -> bool {
+        self.overlaps(other)
+            || self.end.next() == Some(other.start)
+            || other.end.next() == Some(self.start)
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::closed(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Smallest interval holding both of them, gaps included.
+    pub fn hull(&self, other: &Interval<T>) -> Interval<T> {
+        Interval {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    pub fn union(&self, other: &Interval<T>) -> RangeSet<T> {
+        RangeSet::from_iter([*self, *other])
+    }
+
+    /// The integers of the interval that are not in `other`.
+    pub fn difference(&self, other: &Interval<T>) -> RangeSet<T> {
+        RangeSet::from_iter([*self]).difference(&RangeSet::from_iter([*other]))
+    }
+
+    pub fn to_range(&self) -> RangeInclusive<T> {
+        self.start..=self.end
+    }
+}
+
+/// Set of integers stored as sorted intervals that neither overlap nor
+/// touch, so every set has a single representation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Integer> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set, see [`Interval::len`].
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().fold(0u128, |total, interval| {
+            total.saturating_add(interval.len())
+        })
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        self.intervals
+            .get(idx)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    /// Adds `interval`, merging it with the intervals it touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // the intervals before `first` end too early to touch it, the ones
+        // from `last` on start too late
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start && !other.touches(&interval));
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end || other.touches(&interval));
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, other| merged.hull(other));
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Takes the integers of `interval` out of the set.
+    pub fn remove(&mut self, interval: Interval<T>) {
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start);
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end);
+        let mut kept = Vec::new();
+        for other in &self.intervals[first..last] {
+            if let Some(before) = interval.start.prev() {
+                kept.extend(Interval::closed(other.start, before.min(other.end)));
+            }
+            if let Some(after) = interval.end.next() {
+                kept.extend(Interval::closed(after.max(other.start), other.end));
+            }
+        }
+        self.intervals.splice(first..last, kept);
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = self.clone();
+        other
+            .intervals
+            .iter()
+            .for_each(|&interval| result.insert(interval));
+        result
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let (mut i, mut j) = (0, 0);
+        let mut intervals = Vec::new();
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            intervals.extend(a.intersection(b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { intervals }
+    }
+
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = self.clone();
+        other
+            .intervals
+            .iter()
+            .for_each(|&interval| result.remove(interval));
+        result
+    }
+
+    /// Every integer of the set, in order.
+    pub fn values(&self) -> impl Iterator<Item = T> + '_
+    where
+        RangeInclusive<T>: Iterator<Item = T>,
+    {
+        self.intervals.iter().flat_map(Interval::to_range)
+    }
+}
+
+impl<T: Integer> Default for RangeSet<T> {
+    fn default() -> RangeSet<T> {
+        RangeSet::new()
+    }
+}
+
+impl<T: Integer> FromIterator<Interval<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> RangeSet<T> {
+        let mut set = RangeSet::new();
+        intervals
+            .into_iter()
+            .for_each(|interval| set.insert(interval));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(start: i32, end: i32) -> Interval<i32> {
+        Interval::closed(start, end).unwrap()
+    }
+
+    fn set(spans: &[(i32, i32)]) -> RangeSet<i32> {
+        spans
+            .iter()
+            .map(|&(start, end)| closed(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(Interval::closed(5, 4), None);
+        assert_eq!(Interval::half_open(4u8, 4), None);
+        assert_eq!(Interval::half_open(0u8, 0), None);
+        assert_eq!(Interval::half_open(2, 5), Interval::closed(2, 4));
+        assert_eq!(closed(2, 4).len(), 3);
+        assert_eq!(Interval::closed(i8::MIN, i8::MAX).unwrap().len(), 256);
+        assert_eq!(Interval::closed(0, u128::MAX).unwrap().len(), u128::MAX);
+
+        let (a, b) = (closed(2, 8), closed(3, 7));
+        assert!(a.contains_interval(&b) && !b.contains_interval(&a));
+        assert!(a.contains(8) && !a.contains(9));
+        assert!(closed(5, 7).overlaps(&closed(7, 9)));
+        assert!(!closed(2, 4).overlaps(&closed(6, 8)));
+        assert!(closed(2, 4).touches(&closed(5, 8)));
+        assert!(!closed(2, 4).touches(&closed(6, 8)));
+        assert_eq!(closed(5, 7).intersection(&closed(7, 9)), Some(closed(7, 7)));
+        assert_eq!(closed(2, 4).intersection(&closed(6, 8)), None);
+    }
+
+    #[test]
+    fn test_interval_union_and_difference() {
+        assert_eq!(closed(2, 4).union(&closed(5, 8)), set(&[(2, 8)]));
+        assert_eq!(closed(2, 4).union(&closed(6, 8)), set(&[(2, 4), (6, 8)]));
+        assert_eq!(
+            closed(2, 8).difference(&closed(4, 5)),
+            set(&[(2, 3), (6, 8)])
+        );
+        assert_eq!(closed(2, 8).difference(&closed(0, 5)), set(&[(6, 8)]));
+        assert!(closed(2, 8).difference(&closed(1, 9)).is_empty());
+        let full = Interval::closed(u8::MIN, u8::MAX).unwrap();
+        assert_eq!(
+            full.difference(&Interval::single(0)).intervals(),
+            [Interval::closed(1, 255).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_range_set() {
+        let mut ranges = set(&[(10, 12), (1, 3), (5, 6)]);
+        assert_eq!(
+            ranges.intervals(),
+            [closed(1, 3), closed(5, 6), closed(10, 12)]
+        );
+        // adjacent spans are merged
+        ranges.insert(closed(4, 4));
+        assert_eq!(ranges, set(&[(1, 6), (10, 12)]));
+        ranges.insert(closed(0, 11));
+        assert_eq!(ranges, set(&[(0, 12)]));
+        ranges.remove(closed(3, 5));
+        assert_eq!(ranges, set(&[(0, 2), (6, 12)]));
+        assert_eq!(ranges.len(), 10);
+        assert!(ranges.contains(6) && !ranges.contains(4) && !ranges.contains(13));
+        assert_eq!(
+            ranges.values().take(4).collect::<Vec<i32>>(),
+            vec![0, 1, 2, 6]
+        );
+
+        let other = set(&[(2, 7), (11, 20)]);
+        assert_eq!(ranges.union(&other), set(&[(0, 20)]));
+        assert_eq!(
+            ranges.intersection(&other),
+            set(&[(2, 2), (6, 7), (11, 12)])
+        );
+        assert_eq!(ranges.difference(&other), set(&[(0, 1), (8, 10)]));
+        assert_eq!(other.difference(&ranges), set(&[(3, 5), (13, 20)]));
+        assert!(RangeSet::<i32>::default().is_empty());
+    }
+
+    #[test]
+    fn test_huge_ranges() {
+        let big = Interval::closed(1u64, 4_000_000_000).unwrap();
+        let other = Interval::closed(3_999_999_999, 5_000_000_000).unwrap();
+        assert!(big.overlaps(&other));
+        assert_eq!(big.union(&other).len(), 5_000_000_000);
+        assert_eq!(big.difference(&other).len(), 3_999_999_998);
+    }
+}
@@ -0,0 +1,430 @@
+//! Small parser combinators working on the borrowed puzzle input.
+//!
+//! A parser is any `Fn(&str) -> Step<T>`. On success it returns the input
+//! left after what it read together with the value, on failure the position
+//! it failed at. A parser failing right where it started has not read
+//! anything, so [`many`], [`sep_by`] and [`alt`] are free to stop there or to
+//! try something else. A failure further on means the input was recognised
+//! but is malformed, and it is reported as is.
+
+use std::str::FromStr;
+
+use crate::common::strip_bom;
+use crate::error::{Result, SolveError};
+
+/// Reason a parser failed and the input left at that point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure<'a> {
+    at: &'a str,
+    message: String,
+}
+
+impl<'a> Failure<'a> {
+    pub fn new(at: &'a str, message: impl Into<String>) -> Failure<'a> {
+        Failure {
+            at,
+            message: message.into(),
+        }
+    }
+
+    /// Whether the failure happened at the very start of `input`.
+    fn is_at(&self, input: &str) -> bool {
+        self.at.len() == input.len()
+    }
+}
+
+pub type Step<'a, T> = std::result::Result<(&'a str, T), Failure<'a>>;
+
+/// Runs `parser` over the whole `input`, only whitespace may be left over.
+/// A failure is reported with the line and the column it happened at.
+pub fn parse_all<'a, T>(
+    day: u32,
+    input: &'a str,
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+) -> Result<T> {
+    let input = strip_bom(input);
+    let failure = match parser(input) {
+        Ok((rest, value)) if rest.trim().is_empty() => return Ok(value),
+        Ok((rest, _)) => {
+            let rest = rest.trim_start();
+            let unexpected = rest.lines().next().unwrap_or(rest);
+            Failure::new(rest, format!("Unexpected '{unexpected}'"))
+        }
+        Err(failure) => failure,
+    };
+    let before = &input[..input.len() - failure.at.len()];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let column = before[line_start..].chars().count() + 1;
+    Err(SolveError::new(day, failure.message)
+        .at_line(line)
+        .at_column(column))
+}
+
+/// Reads exactly `text`.
+pub fn literal<'a>(text: &'static str) -> impl Fn(&'a str) -> Step<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(text) {
+        Some(rest) => Ok((rest, &input[..text.len()])),
+        None => Err(Failure::new(input, format!("Expected '{text}'"))),
+    }
+}
+
+/// Reads a line break, either `\n` or `\r\n`.
+pub fn newline(input: &str) -> Step<'_, ()> {
+    match input
+        .strip_prefix("\r\n")
+        .or_else(|| input.strip_prefix('\n'))
+    {
+        Some(rest) => Ok((rest, ())),
+        None => Err(Failure::new(input, "Expected the end of the line")),
+    }
+}
+
+/// Reads an empty line between two blocks.
+pub fn blank_line(input: &str) -> Step<'_, ()> {
+    let (rest, _) = newline(input)?;
+    newline(rest).map_err(|_| Failure::new(input, "Expected an empty line"))
+}
+
+/// Reads as many characters as possible matching `predicate`, maybe none.
+pub fn take_while<'a>(predicate: impl Fn(char) -> bool) -> impl Fn(&'a str) -> Step<'a, &'a str> {
+    move |input: &'a str| {
+        let end = input.find(|ch| !predicate(ch)).unwrap_or(input.len());
+        Ok((&input[end..], &input[..end]))
+    }
+}
+
+/// Like [`take_while`] but at least one character has to match, `what`
+/// describes it in the error.
+pub fn take_while1<'a>(
+    predicate: impl Fn(char) -> bool,
+    what: &'static str,
+) -> impl Fn(&'a str) -> Step<'a, &'a str> {
+    let take = take_while(predicate);
+    move |input: &'a str| match take(input)? {
+        (_, "") => Err(Failure::new(input, format!("Expected {what}"))),
+        step => Ok(step),
+    }
+}
+
+/// Reads a single character matching `predicate`.
+pub fn char_if<'a>(
+    predicate: impl Fn(char) -> bool,
+    what: &'static str,
+) -> impl Fn(&'a str) -> Step<'a, char> {
+    move |input: &'a str| match input.chars().next() {
+        Some(ch) if predicate(ch) => Ok((&input[ch.len_utf8()..], ch)),
+        _ => Err(Failure::new(input, format!("Expected {what}"))),
+    }
+}
+
+/// Skips spaces, not line breaks.
+pub fn spaces(input: &str) -> Step<'_, ()> {
+    let end = input.find(|ch| ch != ' ').unwrap_or(input.len());
+    Ok((&input[end..], ()))
+}
+
+/// Reads the rest of the line, line break excluded.
+pub fn rest_of_line(input: &str) -> Step<'_, &str> {
+    take_while(|ch| ch != '\n' && ch != '\r')(input)
+}
+
+/// Reads an integer, optionally negative, of any type parsed by `FromStr`.
+pub fn number<T: FromStr>(input: &str) -> Step<'_, T> {
+    let sign = usize::from(input.starts_with('-'));
+    let end = input[sign..]
+        .find(|ch: char| !ch.is_ascii_digit())
+        .map_or(input.len(), |idx| idx + sign);
+    if end == sign {
+        return Err(Failure::new(input, "Expected a number"));
+    }
+    match input[..end].parse() {
+        Ok(value) => Ok((&input[end..], value)),
+        Err(_) => Err(Failure::new(
+            input,
+            format!("Number out of range '{}'", &input[..end]),
+        )),
+    }
+}
+
+pub fn map<'a, T, U>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+    fun: impl Fn(T) -> U,
+) -> impl Fn(&'a str) -> Step<'a, U> {
+    move |input: &'a str| parser(input).map(|(rest, value)| (rest, fun(value)))
+}
+
+/// Turns the value with a fallible `fun`, its error is reported where the
+/// value started.
+pub fn try_map<'a, T, U>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+    fun: impl Fn(T) -> std::result::Result<U, String>,
+) -> impl Fn(&'a str) -> Step<'a, U> {
+    move |input: &'a str| {
+        let (rest, value) = parser(input)?;
+        match fun(value) {
+            Ok(value) => Ok((rest, value)),
+            Err(message) => Err(Failure::new(input, message)),
+        }
+    }
+}
+
+pub fn pair<'a, T, U>(
+    first: impl Fn(&'a str) -> Step<'a, T>,
+    second: impl Fn(&'a str) -> Step<'a, U>,
+) -> impl Fn(&'a str) -> Step<'a, (T, U)> {
+    move |input: &'a str| {
+        let (rest, a) = first(input)?;
+        let (rest, b) = second(rest)?;
+        Ok((rest, (a, b)))
+    }
+}
+
+/// Reads `prefix` then `parser`, keeping only the value of `parser`.
+pub fn preceded<'a, P, T>(
+    prefix: impl Fn(&'a str) -> Step<'a, P>,
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+) -> impl Fn(&'a str) -> Step<'a, T> {
+    map(pair(prefix, parser), |(_, value)| value)
+}
+
+/// Reads `parser` then `suffix`, keeping only the value of `parser`.
+pub fn terminated<'a, T, S>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+    suffix: impl Fn(&'a str) -> Step<'a, S>,
+) -> impl Fn(&'a str) -> Step<'a, T> {
+    map(pair(parser, suffix), |(value, _)| value)
+}
+
+pub fn delimited<'a, P, T, S>(
+    prefix: impl Fn(&'a str) -> Step<'a, P>,
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+    suffix: impl Fn(&'a str) -> Step<'a, S>,
+) -> impl Fn(&'a str) -> Step<'a, T> {
+    preceded(prefix, terminated(parser, suffix))
+}
+
+/// `None` when `parser` fails without reading anything.
+pub fn opt<'a, T>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+) -> impl Fn(&'a str) -> Step<'a, Option<T>> {
+    move |input: &'a str| match parser(input) {
+        Ok((rest, value)) => Ok((rest, Some(value))),
+        Err(failure) if failure.is_at(input) => Ok((input, None)),
+        Err(failure) => Err(failure),
+    }
+}
+
+/// Repeats `parser` until it fails without reading anything.
+pub fn many<'a, T>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+) -> impl Fn(&'a str) -> Step<'a, Vec<T>> {
+    move |mut input: &'a str| {
+        let mut values = Vec::new();
+        loop {
+            match parser(input) {
+                Ok((rest, value)) => {
+                    values.push(value);
+                    if rest.len() == input.len() {
+                        return Ok((rest, values));
+                    }
+                    input = rest;
+                }
+                Err(failure) if failure.is_at(input) => return Ok((input, values)),
+                Err(failure) => return Err(failure),
+            }
+        }
+    }
+}
+
+/// Like [`many`] but `parser` has to succeed at least once.
+pub fn many1<'a, T>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+) -> impl Fn(&'a str) -> Step<'a, Vec<T>> {
+    move |input: &'a str| {
+        let (rest, first) = parser(input)?;
+        let (rest, mut others) = many(&parser)(rest)?;
+        others.insert(0, first);
+        Ok((rest, others))
+    }
+}
+
+/// One or more `parser` separated by `separator`. A separator not followed
+/// by another item is left unread.
+pub fn sep_by<'a, T, S>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+    separator: impl Fn(&'a str) -> Step<'a, S>,
+) -> impl Fn(&'a str) -> Step<'a, Vec<T>> {
+    move |input: &'a str| {
+        let (mut input, first) = parser(input)?;
+        let mut values = vec![first];
+        loop {
+            let Ok((rest, _)) = separator(input) else {
+                return Ok((input, values));
+            };
+            match parser(rest) {
+                Ok((rest, value)) => {
+                    values.push(value);
+                    input = rest;
+                }
+                Err(failure) if failure.is_at(rest) => return Ok((input, values)),
+                Err(failure) => return Err(failure),
+            }
+        }
+    }
+}
+
+/// One `parser` per line.
+pub fn lines<'a, T>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+) -> impl Fn(&'a str) -> Step<'a, Vec<T>> {
+    sep_by(parser, newline)
+}
+
+/// One `parser` per block of lines, blocks are separated by an empty line.
+pub fn blocks<'a, T>(
+    parser: impl Fn(&'a str) -> Step<'a, T>,
+) -> impl Fn(&'a str) -> Step<'a, Vec<T>> {
+    sep_by(parser, blank_line)
+}
+
+/// Alternatives tried in order, see [`alt`].
+pub trait Alt<'a, T> {
+    fn choice(&self, input: &'a str) -> Step<'a, T>;
+}
+
+macro_rules! impl_alt {
+    ($first:ident, $($parser:ident),+) => {
+        impl<'a, T, $first, $($parser),+> Alt<'a, T> for ($first, $($parser),+)
+        where
+            $first: Fn(&'a str) -> Step<'a, T>,
+            $($parser: Fn(&'a str) -> Step<'a, T>),+
+        {
+            #[allow(non_snake_case)]
+            fn choice(&self, input: &'a str) -> Step<'a, T> {
+                let ($first, $($parser),+) = self;
+                let step = $first(input);
+                $(
+                    let step = match step {
+                        Err(failure) if failure.is_at(input) => $parser(input),
+                        step => step,
+                    };
+                )+
+                step
+            }
+        }
+    };
+}
+
+impl_alt!(A, B);
+impl_alt!(A, B, C);
+impl_alt!(A, B, C, D);
+impl_alt!(A, B, C, D, E);
+
+/// The first of a tuple of parsers that does not fail right away.
+pub fn alt<'a, T>(parsers: impl Alt<'a, T>) -> impl Fn(&'a str) -> Step<'a, T> {
+    move |input: &'a str| parsers.choice(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(input: &str) -> Step<'_, Vec<i32>> {
+        sep_by(number, literal(", "))(input)
+    }
+
+    #[test]
+    fn test_basic_parsers() {
+        assert_eq!(number::<i32>("-12 a"), Ok((" a", -12)));
+        assert_eq!(
+            number::<u8>("300"),
+            Err(Failure::new("300", "Number out of range '300'"))
+        );
+        assert_eq!(
+            number::<u32>("-"),
+            Err(Failure::new("-", "Expected a number"))
+        );
+        assert_eq!(literal("move ")("move 1"), Ok(("1", "move ")));
+        assert_eq!(rest_of_line("a b\r\nc"), Ok(("\r\nc", "a b")));
+        assert_eq!(newline("\r\nc"), Ok(("c", ())));
+        assert_eq!(spaces("  \n"), Ok(("\n", ())));
+        assert_eq!(
+            char_if(char::is_alphabetic, "a letter")("Ab"),
+            Ok(("b", 'A'))
+        );
+        assert_eq!(
+            take_while1(|ch| ch.is_ascii_digit(), "a digit")("x"),
+            Err(Failure::new("x", "Expected a digit"))
+        );
+    }
+
+    #[test]
+    fn test_combinators() {
+        assert_eq!(numbers("1, 2, 3\n"), Ok(("\n", vec![1, 2, 3])));
+        // the separator is left unread when no item follows it
+        assert_eq!(numbers("1, x"), Ok((", x", vec![1])));
+        assert_eq!(many(literal("ab"))("ababa"), Ok(("a", vec!["ab", "ab"])));
+        assert_eq!(
+            many1(literal("ab"))("a").unwrap_err().message,
+            "Expected 'ab'"
+        );
+        assert_eq!(opt(literal("-"))("1"), Ok(("1", None)));
+        assert_eq!(
+            delimited(literal("["), char_if(|_| true, "a crate"), literal("]"))("[A] "),
+            Ok((" ", 'A'))
+        );
+
+        let sign = alt((map(literal("+"), |_| 1), map(literal("-"), |_| -1)));
+        assert_eq!(sign("-1"), Ok(("1", -1)));
+        assert_eq!(sign("*1"), Err(Failure::new("*1", "Expected '-'")));
+
+        let even = try_map(number::<u32>, |n| match n % 2 {
+            0 => Ok(n),
+            _ => Err(format!("Odd number {n}")),
+        });
+        assert_eq!(even("3"), Err(Failure::new("3", "Odd number 3")));
+    }
+
+    #[test]
+    fn test_lines_and_blocks() {
+        let input = "1, 2\n3\n\n4\n";
+        assert_eq!(
+            parse_all(1, input, blocks(lines(numbers))),
+            Ok(vec![vec![vec![1, 2], vec![3]], vec![vec![4]]])
+        );
+        let input = "1, 2\r\n3\r\n\r\n4\r\n";
+        assert_eq!(
+            parse_all(1, input, blocks(lines(numbers))).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_errors_are_positioned() {
+        let pairs = lines(pair(terminated(number::<i32>, literal(" ")), number::<i32>));
+        assert_eq!(parse_all(1, "1 2\n3 4\n", &pairs), Ok(vec![(1, 2), (3, 4)]));
+        assert_eq!(
+            parse_all(1, "1 2\n3 x\n", &pairs),
+            Err(SolveError::new(1, "Expected a number")
+                .at_line(2)
+                .at_column(3))
+        );
+        assert_eq!(
+            parse_all(1, "1 2\n\n3 4\n", &pairs),
+            Err(SolveError::new(1, "Unexpected '3 4'")
+                .at_line(3)
+                .at_column(1))
+        );
+        assert_eq!(
+            parse_all(1, "\u{feff}ü 1", preceded(literal("ü "), number::<i32>)),
+            Ok(1)
+        );
+        assert_eq!(
+            parse_all(1, "ü x", preceded(literal("ü "), number::<i32>)),
+            Err(SolveError::new(1, "Expected a number")
+                .at_line(1)
+                .at_column(3))
+        );
+    }
+}
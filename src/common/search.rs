@@ -0,0 +1,283 @@
+//! Shortest paths over graphs given by a neighbour function, so that nodes
+//! can be anything hashable: grid positions, points, or whole states.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Nodes from a start to a goal, both included, and the cost of the edges
+/// between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+impl<N, C> Path<N, C> {
+    /// Number of edges walked.
+    pub fn steps(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    pub fn start(&self) -> &N {
+        &self.nodes[0]
+    }
+
+    pub fn goal(&self) -> &N {
+        &self.nodes[self.nodes.len() - 1]
+    }
+}
+
+/// Nodes met so far, each with the index of the node it was reached from.
+struct Visited<N> {
+    nodes: Vec<N>,
+    parents: Vec<Option<usize>>,
+    index: HashMap<N, usize>,
+}
+
+impl<N: Clone + Eq + Hash> Visited<N> {
+    fn new() -> Visited<N> {
+        Visited {
+            nodes: Vec::new(),
+            parents: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Index of `node` and whether it was met for the first time.
+    fn insert(&mut self, node: N, parent: Option<usize>) -> (usize, bool) {
+        if let Some(&idx) = self.index.get(&node) {
+            return (idx, false);
+        }
+        let idx = self.nodes.len();
+        self.index.insert(node.clone(), idx);
+        self.nodes.push(node);
+        self.parents.push(parent);
+        (idx, true)
+    }
+
+    fn path(&self, mut idx: usize) -> Vec<N> {
+        let mut nodes = vec![self.nodes[idx].clone()];
+        while let Some(parent) = self.parents[idx] {
+            nodes.push(self.nodes[parent].clone());
+            idx = parent;
+        }
+        nodes.reverse();
+        nodes
+    }
+}
+
+/// Breadth first search from all of `starts` at once, every edge costs one
+/// step. Returns the shortest path to the first node satisfying `goal`.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let (idx, true) = visited.insert(start, None) {
+            queue.push_back((idx, 0));
+        }
+    }
+    while let Some((idx, steps)) = queue.pop_front() {
+        if goal(&visited.nodes[idx]) {
+            return Some(Path {
+                nodes: visited.path(idx),
+                cost: steps,
+            });
+        }
+        for next in neighbours(&visited.nodes[idx]) {
+            if let (next, true) = visited.insert(next, Some(idx)) {
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Cheapest path from any of `starts` to a node satisfying `goal`, the
+/// neighbours come with the cost of the edge leading to them.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, neighbours, |_| C::default(), goal)
+}
+
+/// Like [`dijkstra`], guided by `heuristic`. The heuristic must never
+/// overestimate the cost left to reach a goal, otherwise the path found
+/// may not be the cheapest.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut visited = Visited::new();
+    // best known cost of every visited node, by index
+    let mut costs = Vec::new();
+    // lowest estimate first, among equal estimates the node furthest along
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if let (idx, true) = visited.insert(start, None) {
+            costs.push(C::default());
+            queue.push((Reverse(heuristic(&visited.nodes[idx])), C::default(), idx));
+        }
+    }
+    while let Some((_, cost, idx)) = queue.pop() {
+        if cost > costs[idx] {
+            // reached again more cheaply since it was queued
+            continue;
+        }
+        if goal(&visited.nodes[idx]) {
+            return Some(Path {
+                nodes: visited.path(idx),
+                cost,
+            });
+        }
+        for (next, edge) in neighbours(&visited.nodes[idx]) {
+            let next_cost = cost + edge;
+            let (next, new) = visited.insert(next, Some(idx));
+            if new {
+                costs.push(next_cost);
+            } else if next_cost < costs[next] {
+                costs[next] = next_cost;
+                visited.parents[next] = Some(idx);
+            } else {
+                continue;
+            }
+            let estimate = next_cost + heuristic(&visited.nodes[next]);
+            queue.push((Reverse(estimate), next_cost, next));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{Direction, Point};
+
+    /// Open cells of a maze, `#` are walls.
+    fn maze<'a>(rows: &'a [&'a str]) -> impl Fn(&Point) -> Vec<Point> + 'a {
+        move |&point: &Point| {
+            Direction::ALL
+                .iter()
+                .map(|direction| point + direction.delta())
+                .filter(|next| {
+                    let row = usize::try_from(next.y).ok().and_then(|y| rows.get(y));
+                    let cell =
+                        row.and_then(|row| row.as_bytes().get(usize::try_from(next.x).ok()?));
+                    cell == Some(&b'.')
+                })
+                .collect()
+        }
+    }
+
+    const MAZE: [&str; 5] = [
+        ".....", //
+        ".###.", //
+        "...#.", //
+        ".#.#.", //
+        ".#...", //
+    ];
+
+    #[test]
+    fn test_bfs() {
+        let goal = Point::new(2, 3);
+        let path = bfs([Point::ORIGIN], maze(&MAZE), |&point| point == goal).unwrap();
+        assert_eq!(path.steps(), 5);
+        assert_eq!(path.cost, 5);
+        assert_eq!(*path.start(), Point::ORIGIN);
+        assert_eq!(*path.goal(), goal);
+        assert!(path
+            .nodes
+            .windows(2)
+            .all(|pair| pair[0].manhattan(pair[1]) == 1));
+
+        // the start closest to the goal wins
+        let starts = [Point::ORIGIN, Point::new(4, 4)];
+        let path = bfs(starts, maze(&MAZE), |&point| point == goal).unwrap();
+        assert_eq!((*path.start(), path.steps()), (Point::new(4, 4), 3));
+
+        let walled = bfs([Point::ORIGIN], maze(&MAZE), |&point| {
+            point == Point::new(1, 1)
+        });
+        assert_eq!(walled, None);
+        let at_start = bfs([Point::ORIGIN], maze(&MAZE), |_| true).unwrap();
+        assert_eq!(at_start.nodes, vec![Point::ORIGIN]);
+    }
+
+    /// Edges of a small weighted graph where the direct edges are not the
+    /// cheapest way.
+    fn roads(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 7), ('c', 2), ('e', 20)],
+            'b' => vec![('d', 1)],
+            'c' => vec![('b', 3), ('d', 8)],
+            'd' => vec![('e', 2)],
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let path = dijkstra(['a'], roads, |&node| node == 'e').unwrap();
+        assert_eq!(path.nodes, vec!['a', 'c', 'b', 'd', 'e']);
+        assert_eq!(path.cost, 8);
+        let path = dijkstra(['a', 'd'], roads, |&node| node == 'e').unwrap();
+        assert_eq!((path.nodes, path.cost), (vec!['d', 'e'], 2));
+        assert_eq!(dijkstra(['e'], roads, |&node| node == 'a'), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let goal = Point::new(30, 30);
+        let mut explored = (0, 0);
+        let open = |count: &mut usize, point: &Point| {
+            *count += 1;
+            Direction::ALL
+                .iter()
+                .map(|direction| (*point + direction.delta(), 1))
+                .filter(|(next, _)| (0..=40).contains(&next.x) && (0..=40).contains(&next.y))
+                .collect::<Vec<(Point, i64)>>()
+        };
+        let guided = astar(
+            [Point::ORIGIN],
+            |point| open(&mut explored.0, point),
+            |point| point.manhattan(goal),
+            |&point| point == goal,
+        )
+        .unwrap();
+        let blind = dijkstra(
+            [Point::ORIGIN],
+            |point| open(&mut explored.1, point),
+            |&point| point == goal,
+        )
+        .unwrap();
+        assert_eq!(guided.cost, 60);
+        assert_eq!(blind.cost, 60);
+        assert_eq!(guided.steps(), 60);
+        assert!(explored.0 < explored.1 / 2, "{explored:?}");
+    }
+}
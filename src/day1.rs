@@ -1,46 +1,433 @@
-use crate::common::read_file;
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fmt,
+    io::{BufRead, BufReader, Read},
+};
 
-fn calc_calories(data: Vec<String>) -> Vec<i64> {
-    let mut result = vec![0];
-    for line in data {
-        match line.parse::<i64>() {
-            Ok(num) => *result.last_mut().unwrap() += num,
-            Err(_) => result.push(0),
+use crate::answer::Answer;
+use crate::common::interval::Interval;
+use crate::common::{lines, read_file, repeat_input, strip_bom};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
+
+const DAY: u32 = 1;
+
+/// An elf, numbered from 0 in the order of the inventory, with the total of
+/// the calories it carries.
+pub type Elf = (usize, i64);
+
+/// The `k` elves carrying the most calories among the ones pushed so far.
+/// Only `k` elves are kept at any time, in a min-heap so that the least
+/// loaded of them is the one pushed out.
+pub struct TopElves {
+    k: usize,
+    // among equal totals the elf coming last is the least loaded, so the
+    // ranking does not depend on the order of the heap
+    heap: BinaryHeap<Reverse<(i64, Reverse<usize>)>>,
+}
+
+impl TopElves {
+    pub fn new(k: usize) -> TopElves {
+        TopElves {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
         }
     }
-    result
+
+    pub fn push(&mut self, elf: usize, total: i64) {
+        let entry = Reverse((total, Reverse(elf)));
+        if self.heap.len() < self.k {
+            self.heap.push(entry);
+        } else if self.heap.peek().is_some_and(|least| entry < *least) {
+            self.heap.pop();
+            self.heap.push(entry);
+        }
+    }
+
+    /// The elves kept, the most loaded first.
+    pub fn into_sorted(self) -> Vec<Elf> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(elf)))| (elf, total))
+            .collect()
+    }
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file(path);
-    let calories = calc_calories(data);
-    (*calories.iter().max().unwrap_or(&0)).to_string()
+/// Reads an inventory from `reader` one line at a time and returns the `k`
+/// elves carrying the most calories, the most loaded first. Memory does not
+/// grow with the size of the inventory, apart from `k`.
+///
+/// Elves are numbered like [`Day1`] parses them, every empty line between
+/// two items starts a new elf. A byte order mark and CRLF line endings are
+/// accepted by both.
+pub fn top_elves(reader: impl Read, k: usize) -> Result<Vec<Elf>> {
+    let mut reader = BufReader::new(reader);
+    let mut top = TopElves::new(k);
+    let mut line = String::new();
+    // the elf being read and its total, with the empty lines seen after it
+    let (mut current, mut blanks) = (None, 0);
+    for number in 1.. {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|err| SolveError::new(DAY, format!("cannot read the inventory: {err}")))?;
+        if read == 0 {
+            break;
+        }
+        let item = line.trim_end_matches(['\n', '\r']);
+        let item = if number == 1 { strip_bom(item) } else { item };
+        if item.is_empty() {
+            blanks += usize::from(current.is_some());
+            continue;
+        }
+        let calories = item.parse::<i64>().map_err(|_| {
+            SolveError::new(DAY, format!("invalid number of calories '{item}'")).at_line(number)
+        })?;
+        current = match current {
+            None => Some((0, calories)),
+            Some((elf, total)) if blanks == 0 => Some((elf, total + calories)),
+            Some((elf, total)) => {
+                top.push(elf, total);
+                // the elves between two consecutive empty lines carry nothing
+                (1..blanks).for_each(|empty| top.push(elf + empty, 0));
+                Some((elf + blanks, calories))
+            }
+        };
+        blanks = 0;
+    }
+    if let Some((elf, total)) = current {
+        top.push(elf, total);
+    }
+    Ok(top.into_sorted())
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file(path);
-    let mut calories = calc_calories(data);
-    calories.sort_by(|a, b| b.cmp(a));
+/// The items carried by every elf, an empty line between two items starts
+/// the next elf. An elf only exists once its first item is read, so empty
+/// lines before the first item or after the last one add no elf.
+fn calc_calories(data: Vec<&str>) -> Result<Vec<Vec<i64>>> {
+    let mut result: Vec<Vec<i64>> = Vec::new();
+    // the empty lines seen since the last item
+    let mut blanks = 0;
+    for (idx, line) in data.iter().enumerate() {
+        if line.is_empty() {
+            blanks += usize::from(!result.is_empty());
+            continue;
+        }
+        let num = line.parse::<i64>().map_err(|_| {
+            SolveError::new(DAY, format!("invalid number of calories '{line}'")).at_line(idx + 1)
+        })?;
+        if result.is_empty() {
+            result.push(Vec::new());
+        }
+        // the elves between two consecutive empty lines carry nothing
+        result.extend((0..blanks).map(|_| Vec::new()));
+        blanks = 0;
+        result.last_mut().unwrap().push(num);
+    }
+    Ok(result)
+}
 
-    let mut result = 0;
-    let mut numbers = 0;
-    for calorie in calories {
-        result += calorie;
-        numbers += 1;
-        if numbers >= 3 {
-            break;
+fn totals(inventory: &[Vec<i64>]) -> impl Iterator<Item = i64> + '_ {
+    inventory.iter().map(|items| items.iter().sum())
+}
+
+const HISTOGRAM_BINS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Figures about the elves of an inventory, shown by `run --stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    /// Total and number of items of every elf, in the order of the inventory.
+    pub elves: Vec<(i64, usize)>,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    /// Rank of every elf, 1 for the most loaded. Elves carrying the same
+    /// total share the best of their ranks and the following ones are
+    /// skipped, like in a competition.
+    pub ranks: Vec<usize>,
+}
+
+impl Statistics {
+    pub fn new(inventory: &[Vec<i64>]) -> Statistics {
+        let elves = inventory
+            .iter()
+            .zip(totals(inventory))
+            .map(|(items, total)| (total, items.len()))
+            .collect::<Vec<(i64, usize)>>();
+        let mut sorted = elves.iter().map(|&(total, _)| total).collect::<Vec<i64>>();
+        sorted.sort_unstable();
+
+        let count = sorted.len().max(1) as f64;
+        let mean = sorted.iter().sum::<i64>() as f64 / count;
+        let median = match sorted.len() {
+            0 => 0.0,
+            len if len % 2 == 1 => sorted[len / 2] as f64,
+            len => (sorted[len / 2 - 1] + sorted[len / 2]) as f64 / 2.0,
+        };
+        // folded from 0.0, an empty `f64` sum is -0.0
+        let variance = sorted
+            .iter()
+            .map(|&total| (total as f64 - mean).powi(2))
+            .fold(0.0, |sum, square| sum + square)
+            / count;
+        let ranks = elves
+            .iter()
+            .map(|&(total, _)| 1 + sorted.len() - sorted.partition_point(|&other| other <= total))
+            .collect();
+        Statistics {
+            elves,
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+            ranks,
         }
     }
 
-    result.to_string()
+    /// Indices of the elves from the most loaded, ties in the order of the
+    /// inventory.
+    pub fn by_rank(&self) -> Vec<usize> {
+        let mut elves = (0..self.elves.len()).collect::<Vec<usize>>();
+        elves.sort_by_key(|&elf| (self.ranks[elf], elf));
+        elves
+    }
+
+    /// Number of elves whose total falls in each of `bins` spans of the same
+    /// width, from the least loaded elf to the most loaded one. There are
+    /// fewer spans when the totals are too close to fill them all.
+    pub fn histogram(&self, bins: usize) -> Vec<(Interval<i64>, usize)> {
+        let totals = self.elves.iter().map(|&(total, _)| total);
+        let (Some(min), Some(max)) = (totals.clone().min(), totals.clone().max()) else {
+            return Vec::new();
+        };
+        let bins = bins.max(1) as i64;
+        let width = (max - min + bins) / bins;
+        let mut counts = vec![0; ((max - min) / width + 1) as usize];
+        totals.for_each(|total| counts[((total - min) / width) as usize] += 1);
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(bin, count)| {
+                let start = min + bin as i64 * width;
+                let span = Interval::closed(start, (start + width - 1).min(max));
+                (
+                    span.expect("every span starts before the largest total"),
+                    count,
+                )
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let items = self.elves.iter().map(|&(_, items)| items).sum::<usize>();
+        writeln!(f, "elves: {}, items: {items}", self.elves.len())?;
+        writeln!(
+            f,
+            "calories per elf: mean {:.1}, median {:.1}, standard deviation {:.1}",
+            self.mean, self.median, self.std_dev
+        )?;
+
+        let histogram = self.histogram(HISTOGRAM_BINS);
+        let most = histogram.iter().map(|&(_, count)| count).max().unwrap_or(0);
+        for (span, count) in histogram {
+            // any elf at all gets a visible bar
+            let bar = (count * HISTOGRAM_WIDTH).div_ceil(most.max(1));
+            writeln!(
+                f,
+                "{:>8} - {:>8} | {:<HISTOGRAM_WIDTH$} {count}",
+                span.start(),
+                span.end(),
+                "#".repeat(bar)
+            )?;
+        }
+
+        writeln!(
+            f,
+            "{:>6} {:>6} {:>6} {:>8}",
+            "rank", "elf", "items", "total"
+        )?;
+        for elf in self.by_rank() {
+            let (total, items) = self.elves[elf];
+            writeln!(f, "{:>6} {elf:>6} {items:>6} {total:>8}", self.ranks[elf])?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<Vec<i64>>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Calorie Counting";
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<i64>>> {
+        calc_calories(lines(input))
+    }
+
+    fn part1(&self, inventory: &Vec<Vec<i64>>) -> Result<Answer> {
+        Ok(totals(inventory).max().unwrap_or(0).into())
+    }
+
+    fn part2(&self, inventory: &Vec<Vec<i64>>) -> Result<Answer> {
+        let mut top = TopElves::new(3);
+        for (elf, total) in totals(inventory).enumerate() {
+            top.push(elf, total);
+        }
+        Ok(top
+            .into_sorted()
+            .iter()
+            .map(|(_, total)| total)
+            .sum::<i64>()
+            .into())
+    }
+
+    fn scale(&self, input: &str, factor: usize) -> Option<String> {
+        Some(repeat_input(input, "\n\n", factor))
+    }
+
+    fn stats(&self, inventory: &Vec<Vec<i64>>) -> Option<String> {
+        Some(Statistics::new(inventory).to_string())
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day1.part1(&Day1.parse(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day1.part2(&Day1.parse(input)?)
+}
+
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
+}
+
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_day1() {
-        println!("{}", run_part1("data/day1.txt".to_string()));
+        assert_eq!(
+            run_part1("data/day1.txt".to_string()),
+            Ok(Answer::Int(71924))
+        );
+    }
+
+    #[test]
+    fn test_top_elves() {
+        let example = read_example("day1");
+        assert_eq!(
+            top_elves(example.as_bytes(), 3),
+            Ok(vec![(3, 24000), (2, 11000), (4, 10000)])
+        );
+        assert_eq!(top_elves(example.as_bytes(), 1), Ok(vec![(3, 24000)]));
+        assert_eq!(top_elves(example.as_bytes(), 0), Ok(vec![]));
+        assert_eq!(top_elves(example.as_bytes(), 10).unwrap().len(), 5);
+        assert_eq!(top_elves("".as_bytes(), 3), Ok(vec![]));
+
+        // ties go to the elf coming first, CRLF and surrounding empty lines
+        // do not change the numbering
+        let inventory = "\r\n5\r\n\r\n3\r\n2\r\n\r\n\r\n1\r\n4\r\n\r\n";
+        assert_eq!(
+            top_elves(inventory.as_bytes(), 4),
+            Ok(vec![(0, 5), (1, 5), (3, 5), (2, 0)])
+        );
+
+        let err = top_elves("1\n\nx\n".as_bytes(), 3).unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "invalid number of calories 'x'").at_line(3)
+        );
+    }
+
+    #[test]
+    fn test_top_elves_agree_with_parse() {
+        let inventory = "\u{feff}1000\r\n2000\r\n\r\n4000\r\n\r\n\r\n500\r\n600\r\n";
+        let parsed = Day1.parse(inventory).unwrap();
+        assert_eq!(
+            parsed,
+            vec![vec![1000, 2000], vec![4000], vec![], vec![500, 600]]
+        );
+        let mut expected = totals(&parsed).enumerate().collect::<Vec<Elf>>();
+        expected.sort_by_key(|&(elf, total)| (Reverse(total), elf));
+        assert_eq!(top_elves(inventory.as_bytes(), 10), Ok(expected));
+    }
+
+    #[test]
+    fn test_top_elves_agree_with_parts() {
+        let input = read_file(DAY, "data/day1.txt".to_string()).unwrap();
+        let inventory = calc_calories(lines(&input)).unwrap();
+        let top = top_elves(input.as_bytes(), 3).unwrap();
+        for &(elf, total) in &top {
+            assert_eq!(inventory[elf].iter().sum::<i64>(), total);
+        }
+        assert_eq!(Answer::from(top[0].1), solve_part1(&input).unwrap());
+        let sum = top.iter().map(|(_, total)| total).sum::<i64>();
+        assert_eq!(Answer::from(sum), solve_part2(&input).unwrap());
+    }
+
+    #[test]
+    fn test_statistics() {
+        let inventory = calc_calories(lines(&read_example("day1"))).unwrap();
+        let stats = Statistics::new(&inventory);
+        assert_eq!(
+            stats.elves,
+            vec![(6000, 3), (4000, 1), (11000, 2), (24000, 3), (10000, 1)]
+        );
+        assert_eq!((stats.mean, stats.median), (11000.0, 10000.0));
+        assert!((stats.std_dev - 6985.7).abs() < 0.1, "{}", stats.std_dev);
+        assert_eq!(stats.ranks, vec![4, 5, 2, 1, 3]);
+        assert_eq!(stats.by_rank(), vec![3, 2, 4, 0, 1]);
+
+        let histogram = stats.histogram(4);
+        let span = |start, end| Interval::closed(start, end).unwrap();
+        assert_eq!(
+            histogram,
+            vec![
+                (span(4000, 9000), 2),
+                (span(9001, 14001), 2),
+                (span(14002, 19002), 0),
+                (span(19003, 24000), 1),
+            ]
+        );
+
+        let report = stats.to_string();
+        assert!(report.starts_with("elves: 5, items: 10\n"));
+        assert!(report.contains("mean 11000.0, median 10000.0"));
+        assert!(report.ends_with("     5      1      1     4000\n"));
+    }
+
+    #[test]
+    fn test_statistics_ties() {
+        // an empty line between two others makes an elf without items
+        let inventory = calc_calories(lines("5\n\n3\n2\n\n\n7\n\n5\n")).unwrap();
+        let stats = Statistics::new(&inventory);
+        assert_eq!(stats.elves[2], (0, 0));
+        assert_eq!(stats.ranks, vec![2, 2, 5, 1, 2]);
+        assert_eq!(stats.by_rank(), vec![3, 0, 1, 4, 2]);
+        assert_eq!(stats.median, 5.0);
+
+        for empty in ["", "\n\n\n"] {
+            let inventory = calc_calories(lines(empty)).unwrap();
+            assert!(inventory.is_empty());
+            let stats = Statistics::new(&inventory);
+            assert!(stats.elves.is_empty() && stats.ranks.is_empty());
+            assert!(stats.to_string().starts_with(
+                "elves: 0, items: 0\ncalories per elf: mean 0.0, median 0.0, standard deviation 0.0\n"
+            ));
+            assert_eq!(top_elves(empty.as_bytes(), 3), Ok(vec![]));
+        }
     }
 }
@@ -1,26 +1,31 @@
 use std::collections::VecDeque;
 
-use crate::common::read_file;
+use crate::answer::Answer;
+use crate::common::{lines, read_file};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-#[derive(Debug)]
-enum Op {
+const DAY: u32 = 10;
+
+#[derive(Debug, Clone)]
+pub enum Op {
     Addx(u32, i32),
     Noop(u32),
 }
 
-struct CPU {
+struct Cpu {
     reg: i32,
     cycle: u32,
     ops: VecDeque<Op>,
     crt: String,
 }
 
-impl CPU {
-    fn new() -> CPU {
-        CPU {
+impl Cpu {
+    fn new(ops: &[Op]) -> Cpu {
+        Cpu {
             reg: 1,
             cycle: 0,
-            ops: VecDeque::new(),
+            ops: ops.iter().cloned().collect(),
             crt: String::new(),
         }
     }
@@ -41,18 +46,6 @@ impl CPU {
         self.cycle += 1;
     }
 
-    fn add_op(&mut self, op: Op) {
-        self.ops.push_back(op);
-    }
-
-    fn parse_op(&mut self, op: &str) {
-        match op.trim().split(' ').collect::<Vec<&str>>()[..] {
-            ["addx", value] => self.add_op(Op::Addx(2, value.parse::<i32>().unwrap())),
-            ["noop"] => self.add_op(Op::Noop(1)),
-            _ => panic!("Unknown operation"),
-        }
-    }
-
     fn draw_px(&mut self, cycle: u32, screen_length: u32) {
         let finished_cycle = (self.cycle as i32) - 1 - (cycle as i32 - screen_length as i32);
         if self.reg >= finished_cycle && self.reg - 3 < finished_cycle {
@@ -67,11 +60,28 @@ impl CPU {
     }
 }
 
-fn sum_at_cycles(data: Vec<String>, cycles: Vec<u32>) -> i32 {
-    let mut cpu = CPU::new();
+fn parse_op(op: &str) -> Result<Op> {
+    match op.trim().split(' ').collect::<Vec<&str>>()[..] {
+        ["addx", value] => match value.parse::<i32>() {
+            Ok(value) => Ok(Op::Addx(2, value)),
+            Err(_) => Err(SolveError::new(DAY, format!("Wrong value '{value}'"))),
+        },
+        ["noop"] => Ok(Op::Noop(1)),
+        _ => Err(SolveError::new(DAY, format!("Unknown operation '{op}'"))),
+    }
+}
+
+fn parse_ops(data: &[&str]) -> Result<Vec<Op>> {
+    data.iter()
+        .enumerate()
+        .map(|(idx, line)| parse_op(line).map_err(|err| err.at_line(idx + 1)))
+        .collect()
+}
+
+fn sum_at_cycles(ops: &[Op], cycles: Vec<u32>) -> i32 {
+    let mut cpu = Cpu::new(ops);
     let mut result = 0;
 
-    data.iter().for_each(|line| cpu.parse_op(line));
     cpu.cycle += 1;
 
     for cycle in cycles {
@@ -84,12 +94,11 @@ fn sum_at_cycles(data: Vec<String>, cycles: Vec<u32>) -> i32 {
     result
 }
 
-fn draw_at_screen(data: Vec<String>, screen_length: u32, screen_height: usize) -> String {
-    let mut cpu = CPU::new();
+fn draw_at_screen(ops: &[Op], screen_length: u32, screen_height: usize) -> String {
+    let mut cpu = Cpu::new(ops);
     let cycles = Vec::from_iter((1..=screen_height).map(|height| screen_length * height as u32));
 
     cpu.draw_eol();
-    data.iter().for_each(|line| cpu.parse_op(line));
 
     for cycle in cycles {
         while cpu.cycle < cycle {
@@ -102,176 +111,76 @@ fn draw_at_screen(data: Vec<String>, screen_length: u32, screen_height: usize) -
     cpu.crt.clone()
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file(path);
-    let cycles = Vec::from_iter([20, 60, 100, 140, 180, 220]);
-    sum_at_cycles(data, cycles).to_string()
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Op>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Cathode-Ray Tube";
+
+    fn parse(&self, input: &str) -> Result<Vec<Op>> {
+        parse_ops(&lines(input))
+    }
+
+    fn part1(&self, ops: &Vec<Op>) -> Result<Answer> {
+        let cycles = Vec::from_iter([20, 60, 100, 140, 180, 220]);
+        Ok(sum_at_cycles(ops, cycles).into())
+    }
+
+    fn part2(&self, ops: &Vec<Op>) -> Result<Answer> {
+        Ok(Answer::grid(&draw_at_screen(ops, 40, 6)))
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day10.part1(&Day10.parse(input)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file(path);
-    draw_at_screen(data, 40, 6)
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day10.part2(&Day10.parse(input)?)
+}
+
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
+}
+
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_part1_and_part2() {
-        let data = vec![
-            "addx 15".to_string(),
-            "addx -11".to_string(),
-            "addx 6".to_string(),
-            "addx -3".to_string(),
-            "addx 5".to_string(),
-            "addx -1".to_string(),
-            "addx -8".to_string(),
-            "addx 13".to_string(),
-            "addx 4".to_string(),
-            "noop".to_string(),
-            "addx -1".to_string(),
-            "addx 5".to_string(),
-            "addx -1".to_string(),
-            "addx 5".to_string(),
-            "addx -1".to_string(),
-            "addx 5".to_string(),
-            "addx -1".to_string(),
-            "addx 5".to_string(),
-            "addx -1".to_string(),
-            "addx -35".to_string(),
-            "addx 1".to_string(),
-            "addx 24".to_string(),
-            "addx -19".to_string(),
-            "addx 1".to_string(),
-            "addx 16".to_string(),
-            "addx -11".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 21".to_string(),
-            "addx -15".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx -3".to_string(),
-            "addx 9".to_string(),
-            "addx 1".to_string(),
-            "addx -3".to_string(),
-            "addx 8".to_string(),
-            "addx 1".to_string(),
-            "addx 5".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx -36".to_string(),
-            "noop".to_string(),
-            "addx 1".to_string(),
-            "addx 7".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 2".to_string(),
-            "addx 6".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 7".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "addx -13".to_string(),
-            "addx 13".to_string(),
-            "addx 7".to_string(),
-            "noop".to_string(),
-            "addx 1".to_string(),
-            "addx -33".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 2".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 8".to_string(),
-            "noop".to_string(),
-            "addx -1".to_string(),
-            "addx 2".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "addx 17".to_string(),
-            "addx -9".to_string(),
-            "addx 1".to_string(),
-            "addx 1".to_string(),
-            "addx -3".to_string(),
-            "addx 11".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx -13".to_string(),
-            "addx -19".to_string(),
-            "addx 1".to_string(),
-            "addx 3".to_string(),
-            "addx 26".to_string(),
-            "addx -30".to_string(),
-            "addx 12".to_string(),
-            "addx -1".to_string(),
-            "addx 3".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx -9".to_string(),
-            "addx 18".to_string(),
-            "addx 1".to_string(),
-            "addx 2".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 9".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx -1".to_string(),
-            "addx 2".to_string(),
-            "addx -37".to_string(),
-            "addx 1".to_string(),
-            "addx 3".to_string(),
-            "noop".to_string(),
-            "addx 15".to_string(),
-            "addx -21".to_string(),
-            "addx 22".to_string(),
-            "addx -6".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "addx 2".to_string(),
-            "addx 1".to_string(),
-            "noop".to_string(),
-            "addx -10".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "addx 20".to_string(),
-            "addx 1".to_string(),
-            "addx 2".to_string(),
-            "addx 2".to_string(),
-            "addx -6".to_string(),
-            "addx -11".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-            "noop".to_string(),
-        ];
-        let cycles = Vec::from_iter([20, 60, 100, 140, 180, 220]);
-        let result = sum_at_cycles(data.clone(), cycles);
-        assert_eq!(result, 13140);
+        let example = read_example("day10");
+        assert_eq!(solve_part1(&example), Ok(Answer::Int(13140)));
+        assert_eq!(
+            solve_part2(&example).unwrap(),
+            Answer::grid(
+                "
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"
+            )
+        );
+    }
 
-        let screen = draw_at_screen(data, 40, 6);
-        println!("{}", screen);
+    #[test]
+    fn test_malformed_input() {
+        let err = solve_part1("noop\naddx 3\nsubx 1\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Unknown operation 'subx 1'").at_line(3)
+        );
+        let err = solve_part2("addx three\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "Wrong value 'three'").at_line(1));
     }
 }
@@ -1,50 +1,38 @@
 use std::{
     collections::{HashMap, VecDeque},
     fmt,
+    sync::Arc,
 };
 
-use regex::Regex;
+use crate::answer::Answer;
+use crate::common::parser::{
+    alt, blocks, char_if, delimited, literal, map, newline, number, pair, parse_all, preceded,
+    sep_by, spaces, try_map, Failure, Step,
+};
+use crate::common::read_file;
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-use crate::common::read_file_split_by;
+const DAY: u32 = 11;
 
-fn parse_monkey_data(input: String) -> HashMap<String, String> {
-    let regex = Regex::new(r"Monkey (?P<id>\d+):\n +Starting items: (?P<items>[\d, ]+)\n +Operation: new = (?P<op_arg1>[\w\d]+) (?P<op_fun>[+*]) (?P<op_arg2>[\w\d]+)\n +Test: divisible by (?P<test_division>\d+)\n   +If true: throw to monkey (?P<if_division_true>\d+)\n   +If false: throw to monkey (?P<if_division_false>\d+)").unwrap();
-    let caps = regex.captures(&input).unwrap();
-    regex
-        .capture_names()
-        .flatten()
-        .filter_map(|n| Some((n.to_string(), caps.name(n)?.as_str().to_string())))
-        .collect::<HashMap<String, String>>()
-}
+/// Monkeys are cloned for every game, so their behaviour is shared.
+type Behaviour = Arc<dyn Fn(u32) -> u32 + Send + Sync>;
 
-fn get_operation(op_fun: String, op_arg1: String, op_arg2: String) -> Box<dyn Fn(u32) -> u32> {
-    let fun = match op_fun.as_str() {
-        "+" => |arg1, arg2| arg1 + arg2,
-        "*" => |arg1, arg2| arg1 * arg2,
-        _ => panic!("Unknown operation"),
+fn get_operation(op_fun: char, op_arg1: Option<u32>, op_arg2: Option<u32>) -> Behaviour {
+    let fun = match op_fun {
+        '+' => |arg1, arg2| arg1 + arg2,
+        _ => |arg1, arg2| arg1 * arg2,
     };
-    Box::new(move |worry_level| {
-        let arg1 = match op_arg1.as_str() {
-            "old" => worry_level,
-            num => num.parse::<u32>().unwrap(),
-        };
-        let arg2 = match op_arg2.as_str() {
-            "old" => worry_level,
-            num => num.parse::<u32>().unwrap(),
-        };
-        fun(arg1, arg2)
+    Arc::new(move |worry_level| {
+        fun(
+            op_arg1.unwrap_or(worry_level),
+            op_arg2.unwrap_or(worry_level),
+        )
     })
 }
 
-fn get_test(
-    test_division: String,
-    if_division_true: String,
-    if_division_false: String,
-) -> Box<dyn Fn(u32) -> u32> {
-    let division = test_division.parse::<u32>().unwrap();
-    let monkey_if_true = if_division_true.parse::<u32>().unwrap();
-    let monkey_if_false = if_division_false.parse::<u32>().unwrap();
-    Box::new(move |worry_level| {
+fn get_test(division: u32, monkey_if_true: u32, monkey_if_false: u32) -> Behaviour {
+    Arc::new(move |worry_level| {
         if worry_level % division == 0 {
             monkey_if_true
         } else {
@@ -53,41 +41,57 @@ fn get_test(
     })
 }
 
-struct Monkey {
-    id: u32,
-    items: VecDeque<u32>,
-    operation: Box<dyn Fn(u32) -> u32>,
-    test: Box<dyn Fn(u32) -> u32>,
+/// Reads the next line of a monkey description, up to its value.
+fn field<'a>(label: &'static str) -> impl Fn(&'a str) -> Step<'a, ()> {
+    map(pair(pair(newline, spaces), literal(label)), |_| ())
 }
 
-impl Monkey {
-    fn from_string(input: String) -> Self {
-        let data = parse_monkey_data(input);
-
-        let id = data["id"].parse::<u32>().unwrap();
-        let items = data["items"]
-            .split(", ")
-            .map(|i| i.parse::<u32>().unwrap())
-            .collect::<VecDeque<u32>>();
-        let operation = get_operation(
-            data["op_fun"].clone(),
-            data["op_arg1"].clone(),
-            data["op_arg2"].clone(),
-        );
-        let test = get_test(
-            data["test_division"].clone(),
-            data["if_division_true"].clone(),
-            data["if_division_false"].clone(),
-        );
+/// An operation argument, `None` stands for the old worry level.
+fn operand(input: &str) -> Step<'_, Option<u32>> {
+    alt((map(literal("old"), |_| None), map(number, Some)))(input)
+}
+
+fn monkey(input: &str) -> Step<'_, Monkey> {
+    let (input, id) = delimited(literal("Monkey "), number, literal(":"))(input)?;
+    let (input, items) = preceded(field("Starting items: "), sep_by(number, literal(", ")))(input)?;
+    let (input, _) = field("Operation: new = ")(input)?;
+    let (input, op_arg1) = operand(input)?;
+    let (input, op_fun) = delimited(
+        literal(" "),
+        char_if(|ch| ch == '+' || ch == '*', "'+' or '*'"),
+        literal(" "),
+    )(input)?;
+    let (input, op_arg2) = operand(input)?;
+    let divisor = try_map(number, |division| match division {
+        0 => Err("Division by zero".to_string()),
+        division => Ok(division),
+    });
+    let (input, division) = preceded(field("Test: divisible by "), divisor)(input)?;
+    let (input, if_true) = preceded(field("If true: throw to monkey "), number)(input)?;
+    let (input, if_false) = preceded(field("If false: throw to monkey "), number)(input)?;
 
-        Self {
+    Ok((
+        input,
+        Monkey {
             id,
-            items,
-            operation,
-            test,
-        }
-    }
+            items: items.into(),
+            operation: get_operation(op_fun, op_arg1, op_arg2),
+            test: get_test(division, if_true, if_false),
+            targets: [if_true, if_false],
+        },
+    ))
+}
 
+#[derive(Clone)]
+pub struct Monkey {
+    id: u32,
+    items: VecDeque<u32>,
+    operation: Behaviour,
+    test: Behaviour,
+    targets: [u32; 2],
+}
+
+impl Monkey {
     fn play(&mut self) -> Option<(u32, u32)> {
         match self.items.pop_front() {
             None => None,
@@ -106,11 +110,39 @@ impl fmt::Display for Monkey {
     }
 }
 
-fn let_monkeys_play(data: Vec<String>, rounds: u32) -> u32 {
-    let mut monkeys: HashMap<u32, Monkey> = HashMap::from_iter(data.iter().map(|input| {
-        let monkey = Monkey::from_string(input.trim().to_string());
-        (monkey.id, monkey)
-    }));
+fn parse_monkeys(input: &str) -> Result<HashMap<u32, Monkey>> {
+    let monkeys = parse_all(DAY, input, |input| {
+        let located = |block| monkey(block).map(|(rest, monkey)| (rest, (block, monkey)));
+        let (rest, list) = blocks(located)(input)?;
+        let mut monkeys = HashMap::new();
+        for (block, monkey) in list {
+            if monkeys.contains_key(&monkey.id) {
+                let message = format!("Monkey {} is defined twice", monkey.id);
+                return Err(Failure::new(block, message));
+            }
+            monkeys.insert(monkey.id, monkey);
+        }
+        Ok((rest, monkeys))
+    })?;
+
+    // monkeys take turns in the order of their ids
+    let ids = monkeys.len() as u32;
+    if let Some(id) = (0..ids).find(|id| !monkeys.contains_key(id)) {
+        return Err(SolveError::new(DAY, format!("Monkey {id} is missing")));
+    }
+    for monkey in monkeys.values() {
+        if let Some(target) = monkey.targets.iter().find(|target| **target >= ids) {
+            let message = format!("Monkey {} throws to unknown monkey {target}", monkey.id);
+            return Err(SolveError::new(DAY, message));
+        }
+    }
+    Ok(monkeys)
+}
+
+fn play_rounds(mut monkeys: HashMap<u32, Monkey>, rounds: u32) -> Result<u32> {
+    if monkeys.len() < 2 {
+        return Err(SolveError::new(DAY, "At least two monkeys are needed"));
+    }
     let mut inspect_counter: HashMap<u32, u32> =
         HashMap::from_iter(monkeys.keys().map(|monkey_id| (*monkey_id, 0)));
 
@@ -128,53 +160,57 @@ fn let_monkeys_play(data: Vec<String>, rounds: u32) -> u32 {
 
     let mut inspections = inspect_counter.values().copied().collect::<Vec<u32>>();
     inspections.sort();
-    inspections[inspections.len() - 1] * inspections[inspections.len() - 2]
+    Ok(inspections[inspections.len() - 1] * inspections[inspections.len() - 2])
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = HashMap<u32, Monkey>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Monkey in the Middle";
+
+    fn parse(&self, input: &str) -> Result<HashMap<u32, Monkey>> {
+        parse_monkeys(input)
+    }
+
+    fn part1(&self, monkeys: &HashMap<u32, Monkey>) -> Result<Answer> {
+        Ok(play_rounds(monkeys.clone(), 20)?.into())
+    }
+
+    fn part2(&self, _: &HashMap<u32, Monkey>) -> Result<Answer> {
+        Ok(Answer::Unimplemented)
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day11.part1(&Day11.parse(input)?)
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file_split_by(path, "\n\n");
-    let_monkeys_play(data, 20).to_string()
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day11.part2(&Day11.parse(input)?)
 }
 
-pub fn run_part2(_: String) -> String {
-    "not implemented".to_string()
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
+}
+
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
-    fn get_test_data() -> Vec<String> {
-        vec![
-            "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3"
-                .to_string(),
-            "Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0"
-                .to_string(),
-            "Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3"
-                .to_string(),
-            "Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1"
-                .to_string(),
-        ]
+    fn get_test_data() -> String {
+        read_example("day11")
+    }
+
+    fn parse_error(data: &str) -> SolveError {
+        parse_monkeys(data).map(|_| ()).unwrap_err()
     }
 
     #[test]
@@ -187,7 +223,7 @@ mod tests {
           If true: throw to monkey 2
           If false: throw to monkey 3",
         );
-        let mut monkey = Monkey::from_string(data);
+        let mut monkey = parse_all(DAY, &data, monkey).unwrap();
         assert_eq!(monkey.play(), Some((3, 500)));
         assert_eq!(monkey.play(), Some((3, 620)));
         assert_eq!(monkey.play(), None);
@@ -195,10 +231,45 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let result = let_monkeys_play(get_test_data(), 20);
-        assert_eq!(result, 10605);
+        let result = parse_monkeys(&get_test_data()).and_then(|monkeys| play_rounds(monkeys, 20));
+        assert_eq!(result, Ok(10605));
     }
 
     #[test]
-    fn test_part2() {}
+    fn test_malformed_input() {
+        let data = get_test_data().replace("old * old", "old / old");
+        let err = parse_error(&data);
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Expected '+' or '*'")
+                .at_line(17)
+                .at_column(24)
+        );
+
+        let data = get_test_data().replace("divisible by 13", "divisible by 0");
+        let err = parse_error(&data);
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Division by zero")
+                .at_line(18)
+                .at_column(22)
+        );
+
+        let data = get_test_data().replace("Monkey 3:", "Monkey 2:");
+        let err = parse_error(&data);
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Monkey 2 is defined twice")
+                .at_line(22)
+                .at_column(1)
+        );
+
+        let data = get_test_data();
+        let (head, tail) = data.split_at(data.find("Monkey 3").unwrap());
+        let data = format!("{head}{}", tail.replace("monkey 1", "monkey 7"));
+        let err = parse_monkeys(&data)
+            .and_then(|monkeys| play_rounds(monkeys, 20))
+            .unwrap_err();
+        assert_eq!(err.message, "Monkey 3 throws to unknown monkey 7");
+    }
 }
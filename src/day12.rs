@@ -1,195 +1,165 @@
-use std::collections::HashMap;
-
-use crate::common::read_file;
+use crate::answer::Answer;
+use crate::common::search::{bfs, Path};
+use crate::common::{read_file, Direction, Grid, Pos};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
+
+const DAY: u32 = 12;
+
+pub struct Matrix {
+    grid: Grid<u32>,
+    start: Pos,
+    end: Pos,
+}
 
-struct Matrix {
-    grid: Vec<Vec<u32>>,
-    start: (usize, usize),
-    end: Vec<(usize, usize)>,
+fn parse_matrix(input: &str) -> Result<Matrix> {
+    let (mut start, mut end) = (None, None);
+    let grid = Grid::parse(DAY, input, |pos, ch| match ch {
+        'S' => {
+            start = Some(pos);
+            Ok(1)
+        }
+        'E' => {
+            end = Some(pos);
+            Ok(26)
+        }
+        'a'..='z' => {
+            let n: u32 = ch.into();
+            Ok(n - 96)
+        }
+        ch => Err(SolveError::new(DAY, format!("Unknown elevation '{ch}'"))),
+    })?;
+    match (start, end) {
+        (Some(start), Some(end)) => Ok(Matrix { grid, start, end }),
+        (None, _) => Err(SolveError::new(DAY, "Missing start position 'S'")),
+        (_, None) => Err(SolveError::new(DAY, "Missing end position 'E'")),
+    }
 }
 
-fn parse_matrix(data: Vec<String>) -> Matrix {
-    let (mut start, mut end) = ((0, 0), (0, 0));
-    let grid = data
+/// The squares one step away from `pos` that are at most one higher.
+fn get_surrounding(grid: &Grid<u32>, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+    Direction::ALL
         .iter()
-        .enumerate()
-        .map(|(i, line)| {
-            line.chars()
-                .enumerate()
-                .map(|(j, ch)| match ch {
-                    'S' => {
-                        start = (i, j);
-                        1
-                    }
-                    'E' => {
-                        end = (i, j);
-                        26
-                    }
-                    ch => {
-                        let n: u32 = ch.into();
-                        n - 96
-                    }
-                })
-                .collect::<Vec<u32>>()
-        })
-        .collect();
-    Matrix { grid, start, end: vec![end] }
+        .filter_map(move |&direction| grid.step(pos, direction))
+        .filter(move |&next| grid[pos] + 1 >= grid[next])
 }
 
-fn invert_matrix(mut matrix: Matrix) -> Matrix {
-    let mut start = (0, 0);
-    let mut end = Vec::new();
-
-    for i in 0..matrix.grid.len() {
-        for j in 0..matrix.grid[i].len() {
-            if i == matrix.end[0].0 && j == matrix.end[0].1 {
-                start = (i, j);
-            } else if matrix.grid[i][j] == 1 {
-                end.push((i, j));
-            }
-            matrix.grid[i][j] = 27 - matrix.grid[i][j];
-        }
+#[allow(dead_code)]
+fn print_path_in_matrix(matrix: &Matrix, path: &Path<Pos, usize>) {
+    let mut picture = matrix.grid.map(|&height| char::from(height as u8 + 96));
+    for &pos in &path.nodes {
+        picture[pos] = '■';
     }
+    println!("{picture}")
+}
 
-    matrix.start = start;
-    matrix.end = end;
-    matrix
+/// Shortest path climbing from any of `starts` to the end.
+fn find_shortest_path(
+    matrix: &Matrix,
+    starts: impl IntoIterator<Item = Pos>,
+) -> Option<Path<Pos, usize>> {
+    bfs(
+        starts,
+        |&pos| get_surrounding(&matrix.grid, pos),
+        |&pos| pos == matrix.end,
+    )
 }
 
-fn get_surrounding(grid: &Vec<Vec<u32>>, path: &Vec<(usize, usize)>) -> Vec<(usize, usize)> {
-    let mut result = Vec::new();
-    if let Some((pos_i, pos_j)) = path.last().cloned() {
-        for (i, j) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
-            if pos_i == 0 && i == -1
-                || pos_j == 0 && j == -1
-                || pos_i == grid.len() - 1 && i == 1
-                || pos_j == grid[pos_i].len() - 1 && j == 1
-            {
-                continue;
-            }
-            let (new_i, new_j) = ((pos_i as i32 + i) as usize, (pos_j as i32 + j) as usize);
-            if path.contains(&(new_i, new_j)) {
-                continue;
-            }
-            if grid[pos_i][pos_j] + 1 >= grid[new_i][new_j]
-            {
-                result.push((new_i, new_j));
-            }
-        }
-    }
-    result
+/// Every square at the lowest elevation, the start included.
+fn lowest_squares(matrix: &Matrix) -> impl Iterator<Item = Pos> + '_ {
+    matrix
+        .grid
+        .iter()
+        .filter(|&(_, &height)| height == 1)
+        .map(|(pos, _)| pos)
 }
 
-fn get_id(path: &Vec<(usize, usize)>) -> i64 {
-    let mut hashcode = 0;
-    for (idx, (pos_i, pos_j)) in path.iter().enumerate() {
-        let number = idx as i64 + 1;
-        hashcode += number * (pos_i + 1) as i64 * 3 + number * (pos_j + 1) as i64 * 11;
+fn get_number_of_steps(path: Option<Path<Pos, usize>>) -> Result<usize> {
+    match path {
+        Some(path) => Ok(path.steps()),
+        None => Err(SolveError::new(DAY, "No path found")),
     }
-    hashcode * path.len() as i64
 }
 
-#[allow(dead_code)]
-fn print_path_in_matrix(matrix: &Matrix, path: &Vec<(usize, usize)>) {
-    for i in 0..matrix.grid.len() {
-        for j in 0..matrix.grid[i].len() {
-            if path.contains(&(i, j)) {
-                print!("■");
-            } else {
-                let n = matrix.grid[i][j] as u8 + 96;
-                let ch: char = n.into();
-                print!("{}", ch);
-            }
-        }
-        println!();
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Matrix;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Hill Climbing Algorithm";
+
+    fn parse(&self, input: &str) -> Result<Matrix> {
+        parse_matrix(input)
     }
-    println!()
-}
 
-fn find_shortest_paths(matrix: Matrix) -> Option<Vec<(usize, usize)>> {
-    let mut path = vec![matrix.start.clone()];
-    let mut found_paths = Vec::new();
-    let mut branches = HashMap::new();
-    let mut visited = HashMap::new();
-    let mut backtracking = false;
+    fn part1(&self, matrix: &Matrix) -> Result<Answer> {
+        let path = find_shortest_path(matrix, [matrix.start]);
+        Ok(get_number_of_steps(path)?.into())
+    }
 
-    while let Some(item) = path.last() {
-        if matrix.end.contains(&item) {
-            found_paths.push(path.clone());
-        }
-        let previous_path_len = visited.get(item).copied().unwrap_or(10000);
-        let next = if backtracking || previous_path_len > path.len() {
-            visited.insert(item.clone(), path.len());
-            let path_id = get_id(&path);
-            match branches.get_mut(&path_id) {
-                None => {
-                    let surrounding = get_surrounding(&matrix.grid, &path);
-                    branches.insert(path_id.clone(), surrounding);
-                    branches.get_mut(&path_id).unwrap().pop()
-                }
-                Some(surrounding) => surrounding.pop(),
-            }
-        } else {
-            None
-        };
-        backtracking = next.is_none();
-        match next {
-            None => _ = path.pop(),
-            Some(item) => path.push(item),
-        }
+    fn part2(&self, matrix: &Matrix) -> Result<Answer> {
+        let path = find_shortest_path(matrix, lowest_squares(matrix));
+        Ok(get_number_of_steps(path)?.into())
     }
+}
 
-    let min_length = found_paths.iter().map(|path| path.len()).min().unwrap_or(0);
-    found_paths
-        .iter()
-        .filter(|path| path.len() == min_length)
-        .next()
-        .cloned()
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day12.part1(&Day12.parse(input)?)
 }
 
-fn get_number_of_steps(path: Option<Vec<(usize, usize)>>) -> u32 {
-    match path {
-        Some(p) => (p.len() as i32 - 1) as u32,
-        None => 0,
-    }
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day12.part2(&Day12.parse(input)?)
 }
 
-pub fn run_part1(path: String) -> String {
-    let matrix = parse_matrix(read_file(path));
-    get_number_of_steps(find_shortest_paths(matrix)).to_string()
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let matrix = parse_matrix(read_file(path));
-    let inverted_matrix = invert_matrix(matrix);
-    get_number_of_steps(find_shortest_paths(inverted_matrix)).to_string()
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
-    fn get_test_data() -> Vec<String> {
-        vec![
-            "Sabqponm".to_string(),
-            "abcryxxl".to_string(),
-            "accszExk".to_string(),
-            "acctuvwj".to_string(),
-            "abdefghi".to_string(),
-        ]
+    fn get_test_data() -> String {
+        read_example("day12")
     }
 
     #[test]
     fn test_part1() {
-        let matrix = parse_matrix(get_test_data());
-        let shortest_path = find_shortest_paths(matrix);
-        assert_eq!(get_number_of_steps(shortest_path), 31);
+        let matrix = parse_matrix(&get_test_data()).unwrap();
+        let shortest_path = find_shortest_path(&matrix, [matrix.start]);
+        assert_eq!(get_number_of_steps(shortest_path.clone()), Ok(31));
+
+        let path = shortest_path.unwrap();
+        assert_eq!((*path.start(), *path.goal()), (matrix.start, matrix.end));
+        assert!(path
+            .nodes
+            .windows(2)
+            .all(|step| matrix.grid[step[0]] + 1 >= matrix.grid[step[1]]));
     }
 
     #[test]
     fn test_part2() {
-        let matrix = invert_matrix(parse_matrix(get_test_data()));
-        let shortest_path = find_shortest_paths(matrix);
-        assert_eq!(get_number_of_steps(shortest_path), 29);
+        let matrix = parse_matrix(&get_test_data()).unwrap();
+        let shortest_path = find_shortest_path(&matrix, lowest_squares(&matrix));
+        assert_eq!(get_number_of_steps(shortest_path), Ok(29));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = solve_part1("Sab\nc#E\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Unknown elevation '#'").at_line(2)
+        );
+        let err = solve_part1("Sab\nccd\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "Missing end position 'E'"));
+        let err = solve_part1("Szz\nzzE\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "No path found"));
     }
 }
@@ -1,87 +1,127 @@
-use crate::common::read_file;
-use core::panic;
+use crate::answer::Answer;
+use crate::common::{lines, read_file, repeat_input};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-fn read_file_and_split(path: String) -> Vec<(String, String)> {
-    read_file(path)
+const DAY: u32 = 2;
+
+/// Shape played by the opponent and the letter of the second column, both
+/// numbered from 0 in the order `A`/`B`/`C` and `X`/`Y`/`Z`.
+type Round = (u8, u8);
+
+fn read_and_split(input: &str) -> Result<Vec<Round>> {
+    lines(input)
         .iter()
-        .filter_map(|s| match s.split(' ').collect::<Vec<&str>>()[..] {
-            [a, b] => Some((a.to_string(), b.to_string())),
-            _ => None,
+        .enumerate()
+        .map(|(idx, s)| match *s.as_bytes() {
+            [a @ b'A'..=b'C', b' ', b @ b'X'..=b'Z'] => Ok((a - b'A', b - b'X')),
+            _ => Err(SolveError::new(DAY, format!("Wrong format '{s}'")).at_line(idx + 1)),
         })
-        .collect::<Vec<(String, String)>>()
+        .collect()
+}
+
+/// Score of playing `b` against `a`: 1 to 3 for the shape, plus 3 for a
+/// draw or 6 for a win. Each shape beats the one before it.
+fn get_score((a, b): Round) -> i64 {
+    // 0 for a loss, 1 for a draw and 2 for a win
+    let outcome = (b + 4 - a) % 3;
+    i64::from(b + 1 + outcome * 3)
+}
+
+/// Reads the second column as the outcome to reach, `X` to lose, `Y` to
+/// draw and `Z` to win, and replaces it with the shape to play for it.
+fn map_selected(data: &[Round]) -> Vec<Round> {
+    data.iter()
+        .map(|&(a, outcome)| (a, (a + outcome + 2) % 3))
+        .collect()
 }
 
-fn get_score(a: String, b: String) -> i64 {
-    let mut score = 0;
-    match b.as_str() {
-        "X" => score += 1,
-        "Y" => score += 2,
-        "Z" => score += 3,
-        _ => panic!("Unknown letter"),
+fn get_scores(data: &[Round]) -> i64 {
+    data.iter().map(|&round| get_score(round)).sum()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Round>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Rock Paper Scissors";
+
+    fn parse(&self, input: &str) -> Result<Vec<Round>> {
+        read_and_split(input)
     }
-    match (a.as_str(), b.as_str()) {
-        ("A", "X") | ("B", "Y") | ("C", "Z") => score += 3,
-        ("A", "Y") | ("B", "Z") | ("C", "X") => score += 6,
-        _ => (),
+
+    fn part1(&self, data: &Vec<Round>) -> Result<Answer> {
+        Ok(get_scores(data).into())
+    }
+
+    fn part2(&self, data: &Vec<Round>) -> Result<Answer> {
+        Ok(get_scores(&map_selected(data)).into())
+    }
+
+    fn scale(&self, input: &str, factor: usize) -> Option<String> {
+        Some(repeat_input(input, "\n", factor))
     }
-    score
 }
 
-fn map_selected(data: Vec<(String, String)>) -> Vec<(String, String)> {
-    data.into_iter()
-        .filter_map(|(a, b)| match (a.as_str(), b.as_str()) {
-            ("A", "X") => Some((String::from("A"), String::from("Z"))),
-            ("A", "Y") => Some((String::from("A"), String::from("X"))),
-            ("A", "Z") => Some((String::from("A"), String::from("Y"))),
-            ("B", "X") => Some((String::from("B"), String::from("X"))),
-            ("B", "Y") => Some((String::from("B"), String::from("Y"))),
-            ("B", "Z") => Some((String::from("B"), String::from("Z"))),
-            ("C", "X") => Some((String::from("C"), String::from("Y"))),
-            ("C", "Y") => Some((String::from("C"), String::from("Z"))),
-            ("C", "Z") => Some((String::from("C"), String::from("X"))),
-            _ => None,
-        })
-        .collect::<Vec<(String, String)>>()
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day2.part1(&Day2.parse(input)?)
 }
 
-fn get_scores(data: Vec<(String, String)>) -> i64 {
-    data.iter()
-        .fold(0, |curr, (a, b)| curr + get_score(a.clone(), b.clone()))
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day2.part2(&Day2.parse(input)?)
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file_and_split(path);
-    get_scores(data).to_string()
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file_and_split(path);
-    get_scores(map_selected(data)).to_string()
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_day2_part1() {
-        let data = vec![
-            (String::from("A"), String::from("Y")),
-            (String::from("B"), String::from("X")),
-            (String::from("C"), String::from("Z")),
-        ];
-        assert_eq!(get_scores(data), 15);
-        assert_eq!(run_part1("data/day2.txt".to_string()), "12645");
+        let data = read_and_split(&read_example("day2")).unwrap();
+        assert_eq!(get_scores(&data), 15);
+        assert_eq!(
+            run_part1("data/day2.txt".to_string()),
+            Ok(Answer::Int(12645))
+        );
     }
 
     #[test]
     fn test_day2_part2() {
-        let data = vec![
-            (String::from("A"), String::from("Y")),
-            (String::from("B"), String::from("X")),
-            (String::from("C"), String::from("Z")),
-        ];
-        assert_eq!(get_scores(map_selected(data)), 12);
-        assert_eq!(run_part2("data/day2.txt".to_string()), "11756");
+        let data = read_and_split(&read_example("day2")).unwrap();
+        assert_eq!(get_scores(&map_selected(&data)), 12);
+        assert_eq!(
+            run_part2("data/day2.txt".to_string()),
+            Ok(Answer::Int(11756))
+        );
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = solve_part1("A Y\nB W\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "Wrong format 'B W'").at_line(2));
+        assert!(solve_part1("A  Y\n").is_err());
+        assert!(solve_part1("AX Y\n").is_err());
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let input = read_file(DAY, "data/day2.txt".to_string()).unwrap();
+        let crlf = format!("\u{feff}{}", input.replace('\n', "\r\n"));
+        assert_eq!(solve_part1(&crlf), Ok(Answer::Int(12645)));
+        assert_eq!(solve_part2(&crlf), Ok(Answer::Int(11756)));
+        // a malformed line is shown without its line ending
+        let err = solve_part1("A Y\r\nB W\r\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "Wrong format 'B W'").at_line(2));
     }
 }
@@ -1,71 +1,103 @@
-use crate::common::read_file;
-use std::collections::HashSet;
+use crate::answer::Answer;
+use crate::common::{lines, read_file, repeat_input};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-fn read_file_maybe_split(path: String, split_in_middle: bool) -> Vec<String> {
+const DAY: u32 = 3;
+
+/// Set of items, the bit of an item is its priority.
+type Items = u64;
+
+fn priority(item: u8) -> u32 {
+    match item {
+        b'a'..=b'z' => u32::from(item - b'a') + 1,
+        _ => u32::from(item - b'A') + 27,
+    }
+}
+
+fn items(compartment: &[u8]) -> Items {
+    compartment
+        .iter()
+        .fold(0, |items, &item| items | 1 << priority(item))
+}
+
+/// Items of both compartments of every rucksack, read from the bytes of the
+/// input.
+fn read_rucksacks(input: &str) -> Result<Vec<(Items, Items)>> {
     let mut result = Vec::new();
-    read_file(path).iter().for_each(|line| {
+    for (idx, line) in lines(input).iter().enumerate() {
         if line.is_empty() {
-            return;
+            continue;
         }
-        if split_in_middle {
-            let (first, last) = line.split_at(line.len() / 2);
-            result.push(String::from(first));
-            result.push(String::from(last));
-        } else {
-            result.push(String::from(line));
+        if !line.bytes().all(|item| item.is_ascii_alphabetic()) {
+            return Err(SolveError::new(DAY, format!("Unknown item in '{line}'")).at_line(idx + 1));
         }
-    });
-    result
+        let (first, last) = line.as_bytes().split_at(line.len() / 2);
+        result.push((items(first), items(last)));
+    }
+    Ok(result)
 }
 
-fn char_to_int(ch: char) -> u32 {
-    let shift = if ch.is_lowercase() { 96 } else { 38 };
-    let n: u32 = ch.into();
-    n - shift
+/// Sum of the priorities of the item common to each group, given the items
+/// the group has in common.
+fn find_commons(commons: impl Iterator<Item = Items>) -> Result<i64> {
+    let mut result = 0;
+    for (idx, common) in commons.enumerate() {
+        if common == 0 {
+            return Err(SolveError::new(
+                DAY,
+                format!("No common item in group {}", idx + 1),
+            ));
+        }
+        result += i64::from(common.trailing_zeros());
+    }
+    Ok(result)
 }
 
-fn find_common(data: &[String]) -> char {
-    let mut letters: HashSet<char> = HashSet::from_iter(data[0].chars());
-    data.iter().for_each(|line| {
-        letters = HashSet::from_iter(
-            letters
-                .intersection(&HashSet::from_iter(line.chars()))
-                .copied(),
-        );
-    });
-    *letters.iter().next().unwrap()
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<(Items, Items)>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Rucksack Reorganization";
+
+    fn parse(&self, input: &str) -> Result<Vec<(Items, Items)>> {
+        read_rucksacks(input)
+    }
+
+    fn part1(&self, rucksacks: &Vec<(Items, Items)>) -> Result<Answer> {
+        let commons = rucksacks.iter().map(|&(first, last)| first & last);
+        Ok(find_commons(commons)?.into())
+    }
+
+    /// Groups of three elves, a last incomplete group is left out.
+    fn part2(&self, rucksacks: &Vec<(Items, Items)>) -> Result<Answer> {
+        let commons = rucksacks.chunks_exact(3).map(|group| {
+            group
+                .iter()
+                .fold(!0, |common, &(first, last)| common & (first | last))
+        });
+        Ok(find_commons(commons)?.into())
+    }
+
+    fn scale(&self, input: &str, factor: usize) -> Option<String> {
+        Some(repeat_input(input, "\n", factor))
+    }
 }
 
-fn find_commons(data: Vec<Vec<String>>) -> i64 {
-    let mut result = 0;
-    data.iter()
-        .for_each(|group| result += char_to_int(find_common(group)));
-    result as i64
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day3.part1(&Day3.parse(input)?)
 }
 
-fn group_by(data: Vec<String>, groups: usize) -> Vec<Vec<String>> {
-    let mut result = Vec::new();
-    for i in 0..(data.len() / groups) {
-        result.push(Vec::new());
-        for j in 0..groups {
-            let pos = (i * groups) + j;
-            if data.len() > pos {
-                result[i].push(data[pos].clone());
-            }
-        }
-        if result[result.len() - 1].is_empty() {
-            result.remove(result.len() - 1);
-        }
-    }
-    result
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day3.part2(&Day3.parse(input)?)
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file_maybe_split(path, true);
-    find_commons(group_by(data, 2)).to_string()
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file_maybe_split(path, false);
-    find_commons(group_by(data, 3)).to_string()
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
@@ -1,86 +1,129 @@
-use crate::common::read_file;
-use std::collections::HashSet;
+use crate::answer::Answer;
+use crate::common::interval::Interval;
+use crate::common::{lines, read_file, repeat_input};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-fn parse_range(range: String) -> (i32, i32) {
+const DAY: u32 = 4;
+
+/// Section IDs assigned to an elf.
+type Range = Interval<u64>;
+
+fn parse_range(range: &str) -> Result<Range> {
     match range.split('-').collect::<Vec<&str>>()[..] {
-        [n1, n2] => (n1.parse().unwrap(), n2.parse().unwrap()),
-        _ => panic!("Wrong format"),
+        [n1, n2] => match (n1.parse(), n2.parse()) {
+            (Ok(n1), Ok(n2)) => Interval::closed(n1, n2)
+                .ok_or_else(|| SolveError::new(DAY, format!("Empty range '{range}'"))),
+            _ => Err(SolveError::new(DAY, format!("Wrong format '{range}'"))),
+        },
+        _ => Err(SolveError::new(DAY, format!("Wrong format '{range}'"))),
     }
 }
 
-fn expand_range(range: String) -> HashSet<i32> {
-    let (r1, r2) = parse_range(range);
-    (r1..=r2).collect::<HashSet<i32>>()
+fn parse_pairs(data: Vec<&str>) -> Result<Vec<(Range, Range)>> {
+    data.iter()
+        .enumerate()
+        .map(|(idx, pair)| {
+            let ranges = match pair.split(',').collect::<Vec<&str>>()[..] {
+                [r1, r2] => parse_range(r1).and_then(|r1| Ok((r1, parse_range(r2)?))),
+                _ => Err(SolveError::new(DAY, format!("Wrong format '{pair}'"))),
+            };
+            ranges.map_err(|err| err.at_line(idx + 1))
+        })
+        .collect()
 }
 
-fn range_contains_another(range1: (i32, i32), range2: (i32, i32)) -> bool {
-    let (left1, right1) = range1;
-    let (left2, right2) = range2;
-    left1 >= left2 && right1 <= right2 || left2 >= left1 && right2 <= right1
+fn range_contains_another(range1: Range, range2: Range) -> bool {
+    range1.contains_interval(&range2) || range2.contains_interval(&range1)
 }
 
-fn ranges_overlap(range1: HashSet<i32>, range2: HashSet<i32>) -> bool {
-    range1.intersection(&range2).next().is_some()
+fn count_matches(pairs: &[(Range, Range)], fun: impl Fn(Range, Range) -> bool) -> i32 {
+    pairs.iter().filter(|(r1, r2)| fun(*r1, *r2)).count() as i32
 }
 
-fn count_matches(data: Vec<String>, fun: impl Fn(String, String) -> bool) -> i32 {
-    data.iter()
-        .map(|pair| match pair.split(',').collect::<Vec<&str>>()[..] {
-            [r1, r2] if fun(r1.to_string(), r2.to_string()) => 1,
-            _ => 0,
-        })
-        .sum()
+fn count_containing_matches(pairs: &[(Range, Range)]) -> i32 {
+    count_matches(pairs, range_contains_another)
+}
+
+fn count_overlapping_matches(pairs: &[(Range, Range)]) -> i32 {
+    count_matches(pairs, |r1, r2| r1.overlaps(&r2))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<(Range, Range)>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Camp Cleanup";
+
+    fn parse(&self, input: &str) -> Result<Vec<(Range, Range)>> {
+        parse_pairs(lines(input))
+    }
+
+    fn part1(&self, pairs: &Vec<(Range, Range)>) -> Result<Answer> {
+        Ok(count_containing_matches(pairs).into())
+    }
+
+    fn part2(&self, pairs: &Vec<(Range, Range)>) -> Result<Answer> {
+        Ok(count_overlapping_matches(pairs).into())
+    }
+
+    fn scale(&self, input: &str, factor: usize) -> Option<String> {
+        Some(repeat_input(input, "\n", factor))
+    }
 }
 
-fn count_containing_matches(data: Vec<String>) -> i32 {
-    count_matches(data, |r1, r2| {
-        range_contains_another(parse_range(r1), parse_range(r2))
-    })
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day4.part1(&Day4.parse(input)?)
 }
 
-fn count_overlapping_matches(data: Vec<String>) -> i32 {
-    count_matches(data, |r1, r2| {
-        ranges_overlap(expand_range(r1), expand_range(r2))
-    })
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day4.part2(&Day4.parse(input)?)
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file(path);
-    count_containing_matches(data).to_string()
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file(path);
-    count_overlapping_matches(data).to_string()
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_part1() {
-        let data = vec![
-            "2-4,6-8".to_string(),
-            "2-3,4-5".to_string(),
-            "5-7,7-9".to_string(),
-            "2-8,3-7".to_string(),
-            "6-6,4-6".to_string(),
-            "2-6,4-8".to_string(),
-        ];
-        assert_eq!(count_containing_matches(data), 2);
+        let pairs = parse_pairs(lines(&read_example("day4"))).unwrap();
+        assert_eq!(count_containing_matches(&pairs), 2);
     }
 
     #[test]
     fn test_part2() {
-        let data = vec![
-            "2-4,6-8".to_string(),
-            "2-3,4-5".to_string(),
-            "5-7,7-9".to_string(),
-            "2-8,3-7".to_string(),
-            "6-6,4-6".to_string(),
-            "2-6,4-8".to_string(),
-        ];
-        assert_eq!(count_overlapping_matches(data), 4);
+        let pairs = parse_pairs(lines(&read_example("day4"))).unwrap();
+        assert_eq!(count_overlapping_matches(&pairs), 4);
+    }
+
+    #[test]
+    fn test_huge_ranges() {
+        let pairs = parse_pairs(lines("1-4000000000,3999999999-5000000000\n")).unwrap();
+        assert_eq!(count_containing_matches(&pairs), 0);
+        assert_eq!(count_overlapping_matches(&pairs), 1);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = solve_part1("2-4,6-8\n2-3;4-5\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Wrong format '2-3;4-5'").at_line(2)
+        );
+        let err = solve_part2("2-4,6-8\n2-3,4-x\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "Wrong format '4-x'").at_line(2));
+        let err = solve_part2("2-4,6-8\n4-2,4-5\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "Empty range '4-2'").at_line(2));
     }
 }
@@ -1,8 +1,20 @@
+use crate::answer::Answer;
+use crate::common::parser::{
+    alt, char_if, delimited, lines, literal, map, newline, number, opt, parse_all, preceded,
+    sep_by, spaces, terminated, try_map, Failure, Step,
+};
 use crate::common::read_file;
-use regex::Regex;
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 use std::fmt;
 
-struct Cargo {
+const DAY: u32 = 5;
+
+/// Number of crates to move, from which stack and to which stack.
+type Move = (i32, usize, usize);
+
+#[derive(Clone)]
+pub struct Cargo {
     stacks: Vec<Vec<char>>,
 }
 
@@ -41,23 +53,29 @@ impl Cargo {
         }
     }
 
-    fn process_input(&mut self, line: String, keep_order: bool) {
-        let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-        if let Some(caps) = re.captures(line.as_str()) {
-            self.move_crates(
-                caps[1].parse::<i32>().unwrap(),
-                caps[2].parse::<usize>().unwrap() - 1,
-                caps[3].parse::<usize>().unwrap() - 1,
-                keep_order,
-            )
-        }
+    fn parse_move<'a>(&self, input: &'a str) -> Step<'a, Move> {
+        let stack = || {
+            try_map(number::<usize>, |pos| match pos {
+                pos if (1..=self.stacks.len()).contains(&pos) => Ok(pos - 1),
+                pos => Err(format!("Unknown stack '{pos}'")),
+            })
+        };
+        let (input, crates) = preceded(literal("move "), number)(input)?;
+        let (input, from) = preceded(literal(" from "), stack())(input)?;
+        let (input, to) = preceded(literal(" to "), stack())(input)?;
+        Ok((input, (crates, from, to)))
     }
 
-    fn get_top(&self) -> String {
+    fn get_top(&self) -> Result<String> {
         self.stacks
             .iter()
-            .map(|stack| stack.last().unwrap())
-            .collect::<String>()
+            .enumerate()
+            .map(|(pos, stack)| {
+                stack
+                    .last()
+                    .ok_or_else(|| SolveError::new(DAY, format!("Stack {} is empty", pos + 1)))
+            })
+            .collect::<Result<String>>()
     }
 }
 
@@ -71,83 +89,194 @@ impl fmt::Display for Cargo {
     }
 }
 
-fn init_cargo(data: Vec<String>) -> Cargo {
-    let re = Regex::new(r"(?:\[(?P<crate>\w)\]|(?:( )   ))").unwrap();
-    let mut size = 0;
-    let mut height = 0;
+/// A crate `[X]`, or the blank in place of a missing one.
+fn crate_cell(input: &str) -> Step<'_, Option<char>> {
+    alt((
+        map(
+            delimited(
+                literal("["),
+                char_if(|ch| ch.is_ascii_alphanumeric(), "a crate"),
+                literal("]"),
+            ),
+            Some,
+        ),
+        map(literal("   "), |_| None),
+    ))(input)
+}
 
-    for line in &data {
-        if re.is_match(line.as_str()) {
-            height += 1;
-        } else {
-            size = line
-                .split(' ')
-                .filter_map(|num| num.parse::<usize>().ok())
-                .max()
-                .unwrap();
-            break;
-        }
+fn stack_numbers(input: &str) -> Step<'_, Vec<usize>> {
+    delimited(spaces, sep_by(number, spaces), spaces)(input)
+}
+
+fn init_cargo(mut input: &str) -> Step<'_, Cargo> {
+    let mut rows = Vec::new();
+    while let (rest, Some(row)) = opt(terminated(sep_by(crate_cell, literal(" ")), newline))(input)?
+    {
+        rows.push((input, row));
+        input = rest;
     }
+    let (rest, numbers) = terminated(stack_numbers, newline)(input)?;
+    let size = numbers.into_iter().max().unwrap_or(0);
     let mut cargo = Cargo::new(size);
 
-    for line in data[..height].iter().rev() {
-        re.captures_iter(line.as_str())
-            .enumerate()
-            .for_each(|(pos, caps)| {
-                caps.iter()
-                    .filter_map(|m| match m {
-                        Some(load) if load.as_str().len() == 1 => Some(load.as_str()),
-                        _ => None,
-                    })
-                    .for_each(|load| cargo.add_crate(pos, load.chars().next().unwrap()));
-            });
-    }
-    cargo
+    for (row_start, row) in rows.into_iter().rev() {
+        for (pos, load) in row.into_iter().enumerate() {
+            let Some(load) = load else {
+                continue;
+            };
+            if pos >= size {
+                // every cell is three characters wide, plus a space between them
+                let at = &row_start[pos * 4..];
+                return Err(Failure::new(at, "Crate outside of any stack"));
+            }
+            cargo.add_crate(pos, load);
+        }
+    }
+    Ok((rest, cargo))
+}
+
+fn parse_input(input: &str) -> Result<(Cargo, Vec<Move>)> {
+    parse_all(DAY, input, |input| {
+        let (rest, cargo) = init_cargo(input)?;
+        let (rest, _) = newline(rest)?;
+        let (rest, moves) = lines(|line| cargo.parse_move(line))(rest)?;
+        Ok((rest, (cargo, moves)))
+    })
+}
+
+fn load_cargo(cargo: &mut Cargo, moves: &[Move], keep_order: bool) {
+    for &(crates, from, to) in moves {
+        cargo.move_crates(crates, from, to, keep_order);
+    }
 }
 
-fn load_cargo(cargo: &mut Cargo, data: Vec<String>, keep_order: bool) {
-    for line in data {
-        cargo.process_input(line, keep_order);
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = (Cargo, Vec<Move>);
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Supply Stacks";
+
+    fn parse(&self, input: &str) -> Result<(Cargo, Vec<Move>)> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (cargo, moves): &(Cargo, Vec<Move>)) -> Result<Answer> {
+        let mut cargo = cargo.clone();
+        load_cargo(&mut cargo, moves, false);
+        cargo.get_top().map(Answer::from)
+    }
+
+    fn part2(&self, (cargo, moves): &(Cargo, Vec<Move>)) -> Result<Answer> {
+        let mut cargo = cargo.clone();
+        load_cargo(&mut cargo, moves, true);
+        cargo.get_top().map(Answer::from)
+    }
+
+    /// The moves are played forth and back again, so the crates end up
+    /// where the original moves leave them whenever `factor` is odd.
+    fn scale(&self, input: &str, factor: usize) -> Option<String> {
+        let (cargo, moves) = input.split_once("\n\n")?;
+        let forth = moves.trim_end();
+        let back = forth
+            .lines()
+            .rev()
+            .map(|line| match line.split(' ').collect::<Vec<&str>>()[..] {
+                ["move", crates, "from", from, "to", to] => {
+                    Some(format!("move {crates} from {to} to {from}"))
+                }
+                _ => None,
+            })
+            .collect::<Option<Vec<String>>>()?
+            .join("\n");
+        let moves = (0..factor)
+            .map(|idx| if idx % 2 == 0 { forth } else { &back })
+            .collect::<Vec<&str>>();
+        Some(format!("{cargo}\n\n{}\n", moves.join("\n")))
     }
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file(path);
-    let mut cargo = init_cargo(data.clone());
-    load_cargo(&mut cargo, data, false);
-    cargo.get_top()
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day5.part1(&Day5.parse(input)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file(path);
-    let mut cargo = init_cargo(data.clone());
-    load_cargo(&mut cargo, data, true);
-    cargo.get_top()
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day5.part2(&Day5.parse(input)?)
+}
+
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
+}
+
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_cargo() {
-        let data = vec![
-            "    [D]    ".to_string(),
-            "[N] [C]    ".to_string(),
-            "[Z] [M] [P]".to_string(),
-            " 1   2   3 ".to_string(),
-            "".to_string(),
-            "move 1 from 2 to 1".to_string(),
-            "move 3 from 1 to 3".to_string(),
-            "move 2 from 2 to 1".to_string(),
-            "move 1 from 1 to 2".to_string(),
-        ];
-        let mut cargo = init_cargo(data.clone());
-        load_cargo(&mut cargo, data.clone(), false);
-        assert_eq!(cargo.get_top(), "CMZ".to_string());
-
-        let mut cargo = init_cargo(data.clone());
-        load_cargo(&mut cargo, data.clone(), true);
-        assert_eq!(cargo.get_top(), "MCD".to_string());
+        let (cargo, moves) = parse_input(&read_example("day5")).unwrap();
+        let mut moved = cargo.clone();
+        load_cargo(&mut moved, &moves, false);
+        assert_eq!(moved.get_top(), Ok("CMZ".to_string()));
+
+        let mut cargo = cargo.clone();
+        load_cargo(&mut cargo, &moves, true);
+        assert_eq!(cargo.get_top(), Ok("MCD".to_string()));
+    }
+
+    #[test]
+    fn test_leading_whitespace_is_kept() {
+        // the first crate row of the real input starts with an empty stack,
+        // trimming it would move all of its crates one stack to the left
+        let input = read_file(DAY, "data/day5.txt".to_string()).unwrap();
+        assert!(input.starts_with("    [V]"));
+        assert_eq!(
+            run_part1("data/day5.txt".to_string()),
+            Ok(Answer::from("QGTHFZBHV"))
+        );
+        assert_eq!(
+            run_part2("data/day5.txt".to_string()),
+            Ok(Answer::from("MGDMPSZTM"))
+        );
+
+        let crlf = input.replace('\n', "\r\n");
+        assert_eq!(solve_part1(&crlf), Ok(Answer::from("QGTHFZBHV")));
+        let bom = format!("\u{feff}{crlf}");
+        assert_eq!(solve_part1(&bom), Ok(Answer::from("QGTHFZBHV")));
+    }
+
+    #[test]
+    fn test_scale() {
+        let scaled = Day5.scale(&read_example("day5"), 3).unwrap();
+        assert_eq!(scaled.matches("move").count(), 3 * 4);
+        assert_eq!(solve_part1(&scaled), Ok(Answer::from("CMZ")));
+        assert_eq!(solve_part2(&scaled), Ok(Answer::from("MCD")));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let input = "[A] [B]\n 1   2 \n\nmove 1 from 1 to 3\n";
+        let err = solve_part1(input).unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Unknown stack '3'")
+                .at_line(4)
+                .at_column(18)
+        );
+        let err = solve_part1("[A] [B]\n\nmove 1 from 1 to 2\n").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        let err = solve_part1("    [C]\n[A] [B]\n 1 \n\nmove 1 from 1 to 1\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Crate outside of any stack")
+                .at_line(2)
+                .at_column(5)
+        );
     }
 }
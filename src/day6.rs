@@ -1,15 +1,20 @@
 use std::collections::HashSet;
 
-use crate::common::read_file;
+use crate::answer::Answer;
+use crate::common::{lines, read_file};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-fn read_stream(path: String) -> String {
-    let data = read_file(path);
-    data.first().unwrap().clone()
+const DAY: u32 = 6;
+
+fn read_stream(input: &str) -> String {
+    let data = lines(input);
+    data.first().unwrap().to_string()
 }
 
 fn get_marker(stream: String, length: usize) -> Option<usize> {
     let mut current: Vec<char> = Vec::new();
-    for (idx, ch) in stream.chars().into_iter().enumerate() {
+    for (idx, ch) in stream.chars().enumerate() {
         current.push(ch);
         if current.len() > length {
             current.remove(0);
@@ -21,14 +26,46 @@ fn get_marker(stream: String, length: usize) -> Option<usize> {
     None
 }
 
-pub fn run_part1(path: String) -> String {
-    let stream = read_stream(path);
-    get_marker(stream, 4).unwrap().to_string()
+fn find_marker(stream: String, length: usize) -> Result<usize> {
+    get_marker(stream, length)
+        .ok_or_else(|| SolveError::new(DAY, format!("No marker of length {length} found")))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = String;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Tuning Trouble";
+
+    fn parse(&self, input: &str) -> Result<String> {
+        Ok(read_stream(input))
+    }
+
+    fn part1(&self, stream: &String) -> Result<Answer> {
+        find_marker(stream.clone(), 4).map(Answer::from)
+    }
+
+    fn part2(&self, stream: &String) -> Result<Answer> {
+        find_marker(stream.clone(), 14).map(Answer::from)
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day6.part1(&Day6.parse(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day6.part2(&Day6.parse(input)?)
+}
+
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let stream = read_stream(path);
-    get_marker(stream, 14).unwrap().to_string()
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
@@ -45,5 +82,6 @@ mod tests {
     fn test_part2() {
         let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string();
         assert_eq!(get_marker(input, 14), Some(19));
+        assert!(solve_part2("abcabc").is_err());
     }
 }
@@ -1,8 +1,14 @@
 use std::{collections::HashMap, error::Error, fmt};
 
-use regex::Regex;
+use crate::answer::Answer;
+use crate::common::parser::{
+    alt, lines, literal, map, number, pair, parse_all, preceded, rest_of_line, terminated, Step,
+};
+use crate::common::{read_file, repeat_input};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-use crate::common::read_file;
+const DAY: u32 = 7;
 
 #[derive(Debug)]
 pub struct FileSystemError(String);
@@ -41,21 +47,29 @@ impl Content {
         self.size.is_some()
     }
 
-    fn count_bytes(&self) -> i32 {
+    /// Size of the content. The sizes of the directories in it, itself
+    /// included, are pushed to `sizes` after the ones they contain.
+    fn collect_sizes(&self, sizes: &mut Vec<i32>) -> i32 {
         match self.size {
             Some(size) => size,
             None => {
-                let mut result = 0;
-                for content in self.content.values() {
-                    result += content.count_bytes();
-                }
-                result
+                let total = self
+                    .content
+                    .values()
+                    .map(|content| content.collect_sizes(sizes))
+                    .sum();
+                sizes.push(total);
+                total
             }
         }
     }
 
-    fn iter_dirs(&self) -> impl Iterator<Item = &Self> {
-        self.content.values().filter(|entity| !entity.is_file())
+    /// Size of every directory, computed in a single pass so that each file
+    /// is counted once whatever its depth. The outermost directory is last.
+    fn dir_sizes(&self) -> Vec<i32> {
+        let mut sizes = Vec::new();
+        self.collect_sizes(&mut sizes);
+        sizes
     }
 }
 
@@ -80,7 +94,7 @@ impl FileSystem {
         dir
     }
 
-    pub fn cd(&mut self, name: String) -> Result<(), FileSystemError> {
+    pub fn cd(&mut self, name: String) -> std::result::Result<(), FileSystemError> {
         match name.as_str() {
             ".." => {
                 self.cwd.pop();
@@ -106,7 +120,7 @@ impl FileSystem {
         }
     }
 
-    pub fn mkdir(&mut self, name: String) -> Result<(), FileSystemError> {
+    pub fn mkdir(&mut self, name: String) -> std::result::Result<(), FileSystemError> {
         let dir = self.open();
         match dir.content.get(&name) {
             Some(_) => Err(FileSystemError(
@@ -119,7 +133,11 @@ impl FileSystem {
         }
     }
 
-    pub fn write_bytes(&mut self, name: String, bytes: i32) -> Result<(), FileSystemError> {
+    pub fn write_bytes(
+        &mut self,
+        name: String,
+        bytes: i32,
+    ) -> std::result::Result<(), FileSystemError> {
         let dir = self.open();
         match dir.content.get(&name) {
             Some(_) => Err(FileSystemError(
@@ -134,136 +152,170 @@ impl FileSystem {
     }
 }
 
-fn re_match(regex: &str, text: &str, matches: Option<usize>) -> Option<Vec<String>> {
-    let re = Regex::new(regex).unwrap();
-    if re.is_match(text) {
-        match matches {
-            Some(n) => {
-                let captures = re.captures(text).unwrap();
-                Some(
-                    (1..=n)
-                        .map(|idx| {
-                            captures
-                                .get(idx)
-                                .map_or("".to_string(), |m| m.as_str().to_string())
-                        })
-                        .collect(),
-                )
-            }
-            _ => Some(Vec::new()),
-        }
-    } else {
-        None
-    }
+/// A line of the terminal output.
+enum Command<'a> {
+    Cd(&'a str),
+    Ls,
+    Dir(&'a str),
+    File(i32, &'a str),
 }
 
-fn parse_command(text: &str) -> Option<(&'static str, Vec<String>)> {
-    if let Some(args) = re_match(r"\$ cd (.*)", text, Some(1)) {
-        Some(("cd", args))
-    } else if re_match(r"\$ ls", text, None).is_some() {
-        None
-    } else if let Some(args) = re_match(r"dir (.*)", text, Some(1)) {
-        Some(("dir", args))
-    } else if let Some(args) = re_match(r"(\d+) (.*)", text, Some(2)) {
-        Some(("file", args))
-    } else {
-        None
-    }
+fn command(input: &str) -> Step<'_, Command<'_>> {
+    alt((
+        map(preceded(literal("$ cd "), rest_of_line), Command::Cd),
+        map(literal("$ ls"), |_| Command::Ls),
+        map(preceded(literal("dir "), rest_of_line), Command::Dir),
+        map(
+            pair(terminated(number, literal(" ")), rest_of_line),
+            |(bytes, name)| Command::File(bytes, name),
+        ),
+    ))(input)
 }
 
-fn process_actions(data: Vec<String>) -> FileSystem {
+fn process_actions(input: &str) -> Result<FileSystem> {
     let mut fs = FileSystem::new();
-    for line in data {
-        match parse_command(&line) {
-            Some(("cd", args)) if args.len() == 1 => _ = fs.cd(args[0].clone()),
-            Some(("dir", args)) if args.len() == 1 => _ = fs.mkdir(args[0].clone()),
-            Some(("file", args)) if args.len() == 2 => {
-                _ = fs.write_bytes(args[1].clone(), args[0].clone().parse::<i32>().unwrap())
-            }
-            None => (),
-            _ => println!("err: Unknown command {}", line),
+    for (idx, command) in parse_all(DAY, input, lines(command))?
+        .into_iter()
+        .enumerate()
+    {
+        let error = |err: FileSystemError| SolveError::new(DAY, err.0).at_line(idx + 1);
+        match command {
+            Command::Cd(name) => fs.cd(name.to_string()).map_err(error)?,
+            // listing the same directory twice is harmless, so entries that
+            // already exist are not reported
+            Command::Dir(name) => _ = fs.mkdir(name.to_string()),
+            Command::File(bytes, name) => _ = fs.write_bytes(name.to_string(), bytes),
+            Command::Ls => (),
         }
     }
-    fs
+    Ok(fs)
 }
 
-fn count_size(dir: &Content, max_size: Option<i32>) -> i32 {
-    let mut stack = vec![dir];
-    let mut result = 0;
-    while let Some(dir) = stack.pop() {
-        let bytes = dir.count_bytes();
-        if max_size.is_none() || bytes <= max_size.unwrap() {
-            result += bytes;
-        }
-        dir.iter_dirs().for_each(|dir| stack.push(dir))
-    }
-    result
+fn count_size(sizes: &[i32], max_size: Option<i32>) -> i32 {
+    sizes
+        .iter()
+        .filter(|&&bytes| max_size.is_none_or(|max_size| bytes <= max_size))
+        .sum()
 }
 
-fn find_freeable_space(dir: &Content, min_size: i32) -> i32 {
-    let mut stack = vec![dir];
-    let mut result = Vec::new();
-    while let Some(dir) = stack.pop() {
-        let bytes = dir.count_bytes();
-        if bytes >= min_size {
-            result.push(bytes);
-        }
-        dir.iter_dirs().for_each(|dir| stack.push(dir))
+fn find_freeable_space(sizes: &[i32], min_size: i32) -> Option<i32> {
+    sizes
+        .iter()
+        .filter(|&&bytes| bytes >= min_size)
+        .min()
+        .copied()
+}
+
+/// Space to free so that 30000000 of the 70000000 are unused, given the
+/// size of the outermost directory.
+fn needed_space(used: i32) -> i32 {
+    30000000 - (70000000 - used)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = FileSystem;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "No Space Left On Device";
+
+    fn parse(&self, input: &str) -> Result<FileSystem> {
+        process_actions(input)
+    }
+
+    fn part1(&self, fs: &FileSystem) -> Result<Answer> {
+        Ok(count_size(&fs.root.dir_sizes(), Some(100000)).into())
+    }
+
+    fn part2(&self, fs: &FileSystem) -> Result<Answer> {
+        let sizes = fs.root.dir_sizes();
+        let used = sizes.last().copied().unwrap_or(0);
+        find_freeable_space(&sizes, needed_space(used))
+            .map(Answer::from)
+            .ok_or_else(|| SolveError::new(DAY, "No directory is large enough"))
+    }
+
+    /// Every copy of the terminal output starts over from the root, files
+    /// listed again are ignored so the sizes stay the same.
+    fn scale(&self, input: &str, factor: usize) -> Option<String> {
+        Some(repeat_input(input, "\n$ cd /\n", factor))
     }
-    *result.iter().min().unwrap()
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file(path);
-    let fs = process_actions(data);
-    count_size(&fs.root, Some(100000)).to_string()
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day7.part1(&Day7.parse(input)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file(path);
-    let fs = process_actions(data);
-    let needed_space = 30000000 - (70000000 - fs.root.count_bytes());
-    find_freeable_space(&fs.root, needed_space).to_string()
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day7.part2(&Day7.parse(input)?)
+}
+
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
+}
+
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_part1() {
-        let data = vec![
-            "$ cd /".to_string(),
-            "$ ls".to_string(),
-            "dir a".to_string(),
-            "14848514 b.txt".to_string(),
-            "8504156 c.dat".to_string(),
-            "dir d".to_string(),
-            "$ cd a".to_string(),
-            "$ ls".to_string(),
-            "dir e".to_string(),
-            "29116 f".to_string(),
-            "2557 g".to_string(),
-            "62596 h.lst".to_string(),
-            "$ cd e".to_string(),
-            "$ ls".to_string(),
-            "584 i".to_string(),
-            "$ cd ..".to_string(),
-            "$ cd ..".to_string(),
-            "$ cd d".to_string(),
-            "$ ls".to_string(),
-            "4060174 j".to_string(),
-            "8033020 d.log".to_string(),
-            "5626152 d.ext".to_string(),
-            "7214296 k".to_string(),
-        ];
-        let fs = process_actions(data.clone());
-        let result = count_size(&fs.root, Some(100000));
+        let fs = process_actions(&read_example("day7")).unwrap();
+        let result = count_size(&fs.root.dir_sizes(), Some(100000));
         assert_eq!(result, 95437);
+    }
+
+    #[test]
+    fn test_part2() {
+        let fs = process_actions(&read_example("day7")).unwrap();
+        let sizes = fs.root.dir_sizes();
+        assert_eq!(sizes.len(), 4);
+        assert_eq!(sizes.last(), Some(&48381165));
+        assert_eq!(
+            find_freeable_space(&sizes, needed_space(48381165)),
+            Some(24933642)
+        );
+    }
+
+    #[test]
+    fn test_deep_directories() {
+        // every directory holds a file of one byte and the next directory
+        let mut input = String::from("$ cd /\n");
+        for depth in 0..500 {
+            input += &format!("$ ls\n1 file\ndir d{depth}\n$ cd d{depth}\n");
+        }
+        let sizes = process_actions(&input).unwrap().root.dir_sizes();
+        assert_eq!(sizes.len(), 501);
+        assert_eq!(sizes.first(), Some(&0));
+        assert_eq!(sizes.last(), Some(&500));
+        assert_eq!(count_size(&sizes, None), (1..=500).sum());
+    }
 
-        let fs = process_actions(data);
-        let needed_space = 30000000 - (70000000 - fs.root.count_bytes());
-        let freeable_space = find_freeable_space(&fs.root, needed_space);
-        assert_eq!(freeable_space, 24933642);
+    #[test]
+    fn test_scale() {
+        let scaled = Day7.scale(&read_example("day7"), 3).unwrap();
+        assert_eq!(solve_part1(&scaled), Ok(Answer::Int(95437)));
+        assert_eq!(solve_part2(&scaled), Ok(Answer::Int(24933642)));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = solve_part1("$ cd /\n$ cd a\n").unwrap_err();
+        assert_eq!(err, SolveError::new(DAY, "Directory not found").at_line(2));
+        let err = solve_part1("$ cd /\n$ rm -rf a\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Unexpected '$ rm -rf a'")
+                .at_line(2)
+                .at_column(1)
+        );
+        let err = solve_part1("$ cd /\n$ ls\n12x a\n").unwrap_err();
+        assert_eq!(err.line, Some(3));
     }
 }
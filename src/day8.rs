@@ -1,30 +1,33 @@
-use crate::common::read_file;
-
-fn parse_data(data: Vec<String>) -> Vec<Vec<u32>> {
-    data.iter()
-        .map(|line| {
-            line.chars()
-                .map(|ch| ch.to_digit(10).unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .collect()
+use crate::answer::Answer;
+use crate::common::{read_file, Grid, Offset, Pos, NEIGHBOURS4};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
+
+const DAY: u32 = 8;
+
+fn parse_data(input: &str) -> Result<Grid<u32>> {
+    Grid::parse(DAY, input, |_, ch| {
+        ch.to_digit(10)
+            .ok_or_else(|| SolveError::new(DAY, format!("Unknown tree height '{ch}'")))
+    })
 }
 
-fn is_visible(matrix: &Vec<Vec<u32>>, i: usize, j: usize) -> bool {
-    if i == 0 || j == 0 || i == matrix.len() - 1 || j == matrix[i].len() - 1 {
-        return true;
-    }
+/// Heights of the trees seen from `pos` looking towards `offset`, nearest
+/// first.
+fn line_of_sight(grid: &Grid<u32>, pos: Pos, offset: Offset) -> impl Iterator<Item = u32> + '_ {
+    grid.ray(pos, offset).map(|pos| grid[pos])
+}
 
-    let current = matrix[i][j];
-    matrix[..i].iter().map(|line| line[j]).max().unwrap() < current
-        || matrix[i + 1..].iter().map(|line| line[j]).max().unwrap() < current
-        || *matrix[i][..j].iter().max().unwrap() < current
-        || *matrix[i][j + 1..].iter().max().unwrap() < current
+fn is_visible(grid: &Grid<u32>, pos: Pos) -> bool {
+    let current = grid[pos];
+    NEIGHBOURS4
+        .iter()
+        .any(|&offset| line_of_sight(grid, pos, offset).all(|tree| tree < current))
 }
 
-fn get_distance(trees: &mut impl Iterator<Item = u32>, current: u32) -> u32 {
+fn get_distance(trees: impl Iterator<Item = u32>, current: u32) -> u32 {
     let mut distance = 0;
-    for tree in trees.by_ref() {
+    for tree in trees {
         distance += 1;
         if tree >= current {
             break;
@@ -33,78 +36,91 @@ fn get_distance(trees: &mut impl Iterator<Item = u32>, current: u32) -> u32 {
     distance
 }
 
-fn get_viewing_distance(matrix: &[Vec<u32>], i: usize, j: usize) -> u32 {
-    let current = matrix[i][j];
-    get_distance(&mut matrix[..i].iter().rev().map(|line| line[j]), current)
-        * get_distance(&mut matrix[i + 1..].iter().map(|line| line[j]), current)
-        * get_distance(&mut matrix[i][..j].iter().rev().copied(), current)
-        * get_distance(&mut matrix[i][j + 1..].iter().copied(), current)
+fn get_viewing_distance(grid: &Grid<u32>, pos: Pos) -> u32 {
+    NEIGHBOURS4
+        .iter()
+        .map(|&offset| get_distance(line_of_sight(grid, pos, offset), grid[pos]))
+        .product()
 }
 
-fn count_visible(matrix: Vec<Vec<u32>>) -> u32 {
-    let mut visibility_counter = 0;
-    for i in 0..matrix.len() {
-        for j in 0..matrix[i].len() {
-            if is_visible(&matrix, i, j) {
-                visibility_counter += 1;
-            }
-        }
-    }
-    visibility_counter
+fn count_visible(grid: &Grid<u32>) -> usize {
+    grid.positions()
+        .filter(|&pos| is_visible(grid, pos))
+        .count()
 }
 
-fn get_max_viewing_distance(matrix: Vec<Vec<u32>>) -> u32 {
-    let mut max_distance = 0;
-    for i in 0..matrix.len() {
-        for j in 0..matrix[i].len() {
-            let viewing_distance = get_viewing_distance(&matrix, i, j);
-            if viewing_distance > max_distance {
-                max_distance = viewing_distance;
-            }
-        }
+fn get_max_viewing_distance(grid: &Grid<u32>) -> u32 {
+    grid.positions()
+        .map(|pos| get_viewing_distance(grid, pos))
+        .max()
+        .unwrap_or(0)
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Grid<u32>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Treetop Tree House";
+
+    fn parse(&self, input: &str) -> Result<Grid<u32>> {
+        parse_data(input)
     }
-    max_distance
+
+    fn part1(&self, grid: &Grid<u32>) -> Result<Answer> {
+        Ok(count_visible(grid).into())
+    }
+
+    fn part2(&self, grid: &Grid<u32>) -> Result<Answer> {
+        Ok(get_max_viewing_distance(grid).into())
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day8.part1(&Day8.parse(input)?)
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file(path);
-    let matrix = parse_data(data);
-    count_visible(matrix).to_string()
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day8.part2(&Day8.parse(input)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file(path);
-    let matrix = parse_data(data);
-    get_max_viewing_distance(matrix).to_string()
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
+}
+
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_part1() {
-        let data = vec![
-            "30373".to_string(),
-            "25512".to_string(),
-            "65332".to_string(),
-            "33549".to_string(),
-            "35390".to_string(),
-        ];
-        let matrix = parse_data(data);
-        assert_eq!(count_visible(matrix), 21);
+        let grid = parse_data(&read_example("day8")).unwrap();
+        assert_eq!(count_visible(&grid), 21);
     }
 
     #[test]
     fn test_part2() {
-        let data = vec![
-            "30373".to_string(),
-            "25512".to_string(),
-            "65332".to_string(),
-            "33549".to_string(),
-            "35390".to_string(),
-        ];
-        let matrix = parse_data(data);
-        assert_eq!(get_max_viewing_distance(matrix), 8);
+        let grid = parse_data(&read_example("day8")).unwrap();
+        assert_eq!(get_max_viewing_distance(&grid), 8);
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = solve_part1("303\n2x5\n653\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Unknown tree height 'x'").at_line(2)
+        );
+        let err = solve_part1("303\n25\n653\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Rows of different length").at_line(2)
+        );
     }
 }
@@ -1,169 +1,165 @@
-use std::{fmt, iter::repeat};
+use std::{collections::HashSet, fmt};
 
-use crate::common::read_file;
+use crate::answer::Answer;
+use crate::common::{lines, read_file, Direction, Grid, Point};
+use crate::error::{Result, SolveError};
+use crate::solution::Solution;
 
-type Position = (usize, usize);
+const DAY: u32 = 9;
 
 struct Playground {
-    data: Vec<Vec<u8>>,
-    knots: Vec<Position>,
+    /// Cells the last knot went through, the rope may go arbitrarily far.
+    visited: HashSet<Point>,
+    /// Knot positions, head first, all of them starting at the origin.
+    knots: Vec<Point>,
 }
 
 impl Playground {
-    fn new(size: usize, number_of_knots: usize) -> Playground {
-        let mut data = Vec::from_iter((0..size).map(|_| vec![0; size]));
-        let knot_init: Position = (data.len() / 2, data[0].len() / 2);
-        let knots: Vec<Position> = Vec::from_iter(repeat(knot_init).take(number_of_knots));
-        data[knots[0].0][knots[0].1] = 1;
-        Playground { data, knots }
-    }
-
-    fn update_tail_position(&mut self, head_idx: usize, tail_idx: usize) {
-        let head = self.knots[head_idx];
-        let tail = self.knots[tail_idx];
-        let direction = (head.0 as i16 - tail.0 as i16, head.1 as i16 - tail.1 as i16);
-
-        self.knots[tail_idx] = match direction {
-            // ...    ... | .T.    ... | ...    ... | .H.    .H.
-            // T.H -> .TH | ... -> .T. | H.T -> HT. | ... -> .T.
-            // ...    ... | .H.    .H. | ...    ... | .T.    ...
-            (2, 0) => (tail.0 + 1, tail.1),
-            (0, 2) => (tail.0, tail.1 + 1),
-            (-2, 0) => (tail.0 - 1, tail.1),
-            (0, -2) => (tail.0, tail.1 - 1),
-
-            // ...    ... | .H.    .H. | ..H    ..H
-            // ..H -> .TH | ... -> .T. | ... -> .T.
-            // T..    ... | T..    ... | T..    ...
-            // ------------------------------------
-            // ...    ... | .H.    .H. | H..    H..
-            // H.. -> HT. | ... -> .T. | ... -> .T.
-            // ..T    ... | ..T    ... | ..T    ...
-            // ------------------------------------
-            // ..T    ... | ..T    ... | ..T    ...
-            // H.. -> HT. | ... -> .T. | ... -> .T.
-            // ...    ... | .H.    .H. | H..    ...
-            // ------------------------------------
-            // T..    ... | T..    ... | T..    ...
-            // ... -> .T. | ..H -> .TH | ... -> .T.
-            // .H.    .H. | ...    ... | ..H    ..H
-            (2, -1) | (1, -2) | (2, -2) => (tail.0 + 1, tail.1 - 1),
-            (-2, -1) | (-1, -2) | (-2, -2) => (tail.0 - 1, tail.1 - 1),
-            (-2, 1) | (-1, 2) | (-2, 2) => (tail.0 - 1, tail.1 + 1),
-            (2, 1) | (1, 2) | (2, 2) => (tail.0 + 1, tail.1 + 1),
-
-            // rest of the cases don't require any change
-            _ => tail,
+    fn new(number_of_knots: usize) -> Playground {
+        Playground {
+            visited: HashSet::from([Point::ORIGIN]),
+            knots: vec![Point::ORIGIN; number_of_knots],
         }
     }
 
-    fn update_head_position(&mut self, instruction: &str) {
-        let head = self.knots[0];
-        self.knots[0] = match instruction {
-            "U" => (head.0 - 1, head.1),
-            "D" => (head.0 + 1, head.1),
-            "L" => (head.0, head.1 - 1),
-            "R" => (head.0, head.1 + 1),
-            _ => head,
+    /// A knot that is no longer touching the one in front of it moves one
+    /// step towards it, diagonally if they are not on the same row or column.
+    fn update_tail_position(&mut self, head_idx: usize, tail_idx: usize) {
+        let distance = self.knots[head_idx] - self.knots[tail_idx];
+        if distance.chebyshev() > 1 {
+            self.knots[tail_idx] += distance.signum();
         }
     }
 
-    fn move_knots(&mut self, instruction: String, steps: usize) {
+    fn move_knots(&mut self, direction: Direction, steps: usize) {
         for _ in 0..steps {
-            self.update_head_position(&instruction);
+            self.knots[0] += direction.delta();
             for i in 1..self.knots.len() {
                 self.update_tail_position(i - 1, i);
             }
-            let last_tail = self.knots[self.knots.len() - 1];
-            self.data[last_tail.0][last_tail.1] = 1;
+            self.visited.insert(self.knots[self.knots.len() - 1]);
         }
     }
 
-    fn parse_line(&self, line: String) -> (String, usize) {
-        match line.split(' ').collect::<Vec<&str>>()[..] {
-            [instruction, steps] => (instruction.to_string(), steps.parse::<usize>().unwrap()),
-            _ => panic!("Unknown command"),
+    fn run(&mut self, instructions: &[(Direction, usize)]) {
+        for &(direction, steps) in instructions {
+            self.move_knots(direction, steps);
         }
     }
 
-    fn load_vec(&mut self, data: Vec<String>) {
-        for line in data {
-            let (instruction, steps) = self.parse_line(line);
-            self.move_knots(instruction, steps);
-        }
+    fn sum(&self) -> usize {
+        self.visited.len()
     }
+}
 
-    fn sum(&self) -> u32 {
-        self.data
-            .iter()
-            .map(|col| col.iter().map(|&item| item as u32).sum::<u32>())
-            .sum()
+fn parse_line(line: &str) -> Result<(Direction, usize)> {
+    let unknown = || SolveError::new(DAY, format!("Unknown command '{line}'"));
+    match line.split(' ').collect::<Vec<&str>>()[..] {
+        [instruction, steps] => {
+            let direction = Direction::from_udlr(instruction).ok_or_else(unknown)?;
+            match steps.parse::<usize>() {
+                Ok(steps) => Ok((direction, steps)),
+                Err(_) => Err(SolveError::new(
+                    DAY,
+                    format!("Wrong number of steps '{steps}'"),
+                )),
+            }
+        }
+        _ => Err(unknown()),
     }
 }
 
+fn parse_instructions(data: Vec<&str>) -> Result<Vec<(Direction, usize)>> {
+    data.into_iter()
+        .enumerate()
+        .map(|(idx, line)| parse_line(line).map_err(|err| err.at_line(idx + 1)))
+        .collect()
+}
+
 impl fmt::Display for Playground {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut data = String::new();
-        for i in 0..self.data.len() {
-            for j in 0..self.data[i].len() {
-                let mut rope = false;
-                for (idx, (x, y)) in self.knots.iter().enumerate() {
-                    if i == *x && j == *y {
-                        rope = true;
-                        if idx > 0 {
-                            data.push_str(idx.to_string().as_str());
-                        } else {
-                            data.push('H');
-                        }
-                        break;
-                    }
-                }
-                if !rope {
-                    if self.data[i][j] == 1 {
-                        data.push('#');
-                    } else {
-                        data.push('.');
-                    }
-                }
-            }
-            data.push('\n');
+        // only the area the rope went through is drawn
+        let points = || self.visited.iter().chain(&self.knots);
+        let corner = Point::new(
+            points().map(|point| point.x).min().unwrap_or(0),
+            points().map(|point| point.y).min().unwrap_or(0),
+        ) - Point::ORIGIN;
+        let width = points().map(|point| point.x).max().unwrap_or(0) - corner.x + 1;
+        let height = points().map(|point| point.y).max().unwrap_or(0) - corner.y + 1;
+        let mut picture = Grid::new(width as usize, height as usize, '.');
+        let mut draw = |point: Point, cell: char| {
+            let pos = picture
+                .pos_of(point - corner)
+                .expect("the area holds every point");
+            picture[pos] = cell;
+        };
+        self.visited.iter().for_each(|&point| draw(point, '#'));
+        // the knots in front are drawn last, over the ones behind them
+        for (idx, &knot) in self.knots.iter().enumerate().rev() {
+            draw(
+                knot,
+                match idx {
+                    0 => 'H',
+                    idx => char::from_digit(idx as u32, 36).unwrap_or('T'),
+                },
+            );
         }
-        write!(f, "{}", data)
+        write!(f, "{picture}")
     }
 }
 
-pub fn run_part1(path: String) -> String {
-    let data = read_file(path);
-    let mut playground = Playground::new(1000, 2);
-    playground.load_vec(data);
-    playground.sum().to_string()
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<(Direction, usize)>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Rope Bridge";
+
+    fn parse(&self, input: &str) -> Result<Vec<(Direction, usize)>> {
+        parse_instructions(lines(input))
+    }
+
+    fn part1(&self, instructions: &Vec<(Direction, usize)>) -> Result<Answer> {
+        let mut playground = Playground::new(2);
+        playground.run(instructions);
+        Ok(playground.sum().into())
+    }
+
+    fn part2(&self, instructions: &Vec<(Direction, usize)>) -> Result<Answer> {
+        let mut playground = Playground::new(10);
+        playground.run(instructions);
+        Ok(playground.sum().into())
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day9.part1(&Day9.parse(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day9.part2(&Day9.parse(input)?)
+}
+
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
 }
 
-pub fn run_part2(path: String) -> String {
-    let data = read_file(path);
-    let mut playground = Playground::new(1000, 10);
-    playground.load_vec(data);
-    playground.sum().to_string()
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::read_example;
 
     #[test]
     fn test_part1() {
-        let data = vec![
-            "R 4".to_string(),
-            "U 4".to_string(),
-            "L 3".to_string(),
-            "D 1".to_string(),
-            "R 4".to_string(),
-            "D 1".to_string(),
-            "L 5".to_string(),
-            "R 2".to_string(),
-        ];
-        let mut playground = Playground::new(15, 2);
-        playground.load_vec(data);
+        let input = read_example("day9");
+        let data = lines(&input);
+        let mut playground = Playground::new(2);
+        playground.run(&parse_instructions(data).unwrap());
 
         println!("{}", &playground);
         assert_eq!(playground.sum(), 13);
@@ -171,20 +167,49 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let data = vec![
-            "R 5".to_string(),
-            "U 8".to_string(),
-            "L 8".to_string(),
-            "D 3".to_string(),
-            "R 17".to_string(),
-            "D 10".to_string(),
-            "L 25".to_string(),
-            "U 20".to_string(),
-        ];
-        let mut playground = Playground::new(30, 10);
-        playground.load_vec(data);
+        let input = read_example("day9-larger");
+        let data = lines(&input);
+        let mut playground = Playground::new(10);
+        playground.run(&parse_instructions(data).unwrap());
 
         println!("{}", &playground);
         assert_eq!(playground.sum(), 36);
     }
+
+    #[test]
+    fn test_malformed_input() {
+        let err = solve_part1("R 4\nX 4\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Unknown command 'X 4'").at_line(2)
+        );
+        let err = solve_part1("R four\n").unwrap_err();
+        assert_eq!(err.line, Some(1));
+        let err = solve_part1("R 4\nUP 1\n").unwrap_err();
+        assert_eq!(
+            err,
+            SolveError::new(DAY, "Unknown command 'UP 1'").at_line(2)
+        );
+        assert_eq!(
+            solve_part1("R 1 2\n").unwrap_err().message,
+            "Unknown command 'R 1 2'"
+        );
+        // other notations of the directions are not rope moves
+        for line in ["N 4", "^ 3", "v 2"] {
+            assert_eq!(
+                solve_part1(line),
+                Err(SolveError::new(DAY, format!("Unknown command '{line}'")).at_line(1))
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_walk() {
+        // far beyond any fixed size playground
+        assert_eq!(solve_part1("R 5000\nU 3000\n"), Ok(Answer::from(7999)));
+
+        let mut playground = Playground::new(3);
+        playground.run(&parse_instructions(lines("L 3\nU 2\n")).unwrap());
+        assert_eq!(playground.to_string(), "H...\n12..\n..##\n");
+    }
 }
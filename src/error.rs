@@ -0,0 +1,70 @@
+use std::{error::Error, fmt};
+
+/// Error of a solver, usually caused by a malformed puzzle input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveError {
+    pub day: u32,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl SolveError {
+    pub fn new(day: u32, message: impl Into<String>) -> SolveError {
+        SolveError {
+            day,
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    /// Sets the (1-based) line of the input the error was found at.
+    pub fn at_line(mut self, line: usize) -> SolveError {
+        self.line = Some(line);
+        self
+    }
+
+    /// Sets the (1-based) column, counted in characters, of the error.
+    pub fn at_column(mut self, column: usize) -> SolveError {
+        self.column = Some(column);
+        self
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(
+                f,
+                "day{}, line {line}, column {column}: {}",
+                self.day, self.message
+            ),
+            (Some(line), None) => write!(f, "day{}, line {line}: {}", self.day, self.message),
+            _ => write!(f, "day{}: {}", self.day, self.message),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+pub type Result<T> = std::result::Result<T, SolveError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = SolveError::new(4, "Wrong format");
+        assert_eq!(err.to_string(), "day4: Wrong format");
+        assert_eq!(
+            err.clone().at_line(3).to_string(),
+            "day4, line 3: Wrong format"
+        );
+        assert_eq!(
+            err.at_line(3).at_column(7).to_string(),
+            "day4, line 3, column 7: Wrong format"
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Example inputs of the puzzles, stored as `fixtures/<name>.txt`, and their
+//! answers in `fixtures/answers.txt`. Every registered day gets a test solving
+//! all of its examples.
+
+pub const ANSWERS: &str = "fixtures/answers.txt";
+
+/// Path of the example `--example` solves instead of the real input.
+pub fn example_path(day: u32) -> String {
+    format!("fixtures/day{day}.txt")
+}
+
+#[cfg(test)]
+fn read(path: &str) -> String {
+    crate::common::read_input(path).unwrap_or_else(|err| panic!("cannot read {path}: {err}"))
+}
+
+/// Reads `fixtures/<name>.txt`, for tests that need an example input.
+#[cfg(test)]
+pub(crate) fn read_example(name: &str) -> String {
+    read(&format!("fixtures/{name}.txt"))
+}
+
+/// Solves every example of `day` listed in the answers of the fixtures.
+/// Parts that are not implemented yet are skipped.
+#[cfg(test)]
+fn check_examples(day: u32) {
+    use crate::answer::Answer;
+    use crate::answers::Answers;
+    use crate::solution;
+
+    let puzzle = solution::find(day).expect("the day is registered");
+    let answers = Answers::load(ANSWERS).unwrap();
+    let entries = answers.entries(day);
+    assert!(
+        !entries.is_empty(),
+        "add the examples of day{day} to {ANSWERS}"
+    );
+    for (part, input, expected) in entries {
+        let parsed = puzzle.parse_input(&read(input));
+        let actual = parsed.and_then(|parsed| puzzle.solve(&parsed, part));
+        match actual {
+            Ok(Answer::Unimplemented) => (),
+            actual => assert_eq!(
+                actual,
+                Ok(Answer::parse(expected)),
+                "day{day} part{part} on {input}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // generated by build.rs, one test per registered day
+    include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
+}
@@ -0,0 +1,293 @@
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process,
+    time::Duration,
+};
+
+use crate::bench::format_duration;
+
+pub const DEFAULT_PATH: &str = "data/bench-history.txt";
+
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+const HEADER: &str = "\
+# Benchmark results, one tab separated entry per line:
+# <label>\t<day>\t<stage>\t<input path>\t<median ns>\t<mean ns>
+";
+
+#[derive(Debug, PartialEq)]
+pub struct HistoryError(String);
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid benchmark history: {}", self.0)
+    }
+}
+
+impl Error for HistoryError {}
+
+/// Timing of one stage of a day, `parse` or `solveN`, on one input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub label: String,
+    pub day: u32,
+    pub stage: String,
+    pub input: String,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.label,
+            self.day,
+            self.stage,
+            self.input,
+            self.median.as_nanos(),
+            self.mean.as_nanos()
+        )
+    }
+}
+
+/// Every benchmark result recorded so far, oldest first. Empty lines and
+/// lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct History {
+    entries: Vec<Entry>,
+}
+
+fn parse_nanos(idx: usize, field: &str) -> Result<Duration, HistoryError> {
+    field
+        .parse::<u64>()
+        .map(Duration::from_nanos)
+        .map_err(|_| HistoryError(format!("line {}: invalid time '{field}'", idx + 1)))
+}
+
+impl History {
+    pub fn parse(text: &str) -> Result<History, HistoryError> {
+        let mut entries = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split('\t').collect::<Vec<&str>>()[..] {
+                [label, day, stage, input, median, mean] => entries.push(Entry {
+                    label: label.to_string(),
+                    day: day.parse::<u32>().map_err(|_| {
+                        HistoryError(format!("line {}: day must be a number", idx + 1))
+                    })?,
+                    stage: stage.to_string(),
+                    input: input.to_string(),
+                    median: parse_nanos(idx, median)?,
+                    mean: parse_nanos(idx, mean)?,
+                }),
+                _ => {
+                    return Err(HistoryError(format!(
+                        "line {}: expected 6 tab separated fields",
+                        idx + 1
+                    )))
+                }
+            }
+        }
+        Ok(History { entries })
+    }
+
+    /// Loads the history file, a file that does not exist yet has no entries.
+    /// A file that exists but cannot be read is an error, comparing with it
+    /// would silently compare with nothing.
+    pub fn load(path: &str) -> Result<History, HistoryError> {
+        match fs::read_to_string(path) {
+            Ok(text) => History::parse(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(err) => Err(HistoryError(format!("cannot read {path}: {err}"))),
+        }
+    }
+
+    /// Adds `entries` at the end of the history file, creating it if needed.
+    pub fn append(path: &str, entries: &[Entry]) -> io::Result<()> {
+        let exists = Path::new(path).exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if !exists {
+            file.write_all(HEADER.as_bytes())?;
+        }
+        for entry in entries {
+            writeln!(file, "{entry}")?;
+        }
+        Ok(())
+    }
+
+    /// Latest result of the same stage as `entry`, recorded under `label` if
+    /// given, or under any label otherwise.
+    pub fn baseline(&self, entry: &Entry, label: Option<&str>) -> Option<&Entry> {
+        self.entries.iter().rev().find(|other| {
+            other.day == entry.day
+                && other.stage == entry.stage
+                && other.input == entry.input
+                && label.is_none_or(|label| other.label == label)
+        })
+    }
+}
+
+/// A stage timed again and its result in the history.
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub baseline: Entry,
+    pub current: Entry,
+}
+
+impl Comparison {
+    /// Change of the median time, in percent of the baseline.
+    pub fn change(&self) -> f64 {
+        let baseline = self.baseline.median.as_secs_f64();
+        match baseline {
+            0.0 => 0.0,
+            _ => (self.current.median.as_secs_f64() / baseline - 1.0) * 100.0,
+        }
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change() > threshold
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let current = &self.current;
+        write!(
+            f,
+            "day{} {} {}: {} ({}) -> {} ({:+.1}%)",
+            current.day,
+            current.stage,
+            current.input,
+            format_duration(self.baseline.median),
+            self.baseline.label,
+            format_duration(current.median),
+            self.change()
+        )
+    }
+}
+
+/// Pairs the `current` results with their baseline, skipping the stages that
+/// were never timed before.
+pub fn compare(history: &History, current: &[Entry], label: Option<&str>) -> Vec<Comparison> {
+    current
+        .iter()
+        .filter_map(|entry| {
+            let baseline = history.baseline(entry, label)?;
+            Some(Comparison {
+                baseline: baseline.clone(),
+                current: entry.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Label of the checked out commit, with a `-dirty` suffix when the working
+/// tree has uncommitted changes, or `None` outside of a git repository.
+pub fn git_label() -> Option<String> {
+    let output = process::Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .output()
+        .ok()?;
+    let label = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !label.is_empty()).then_some(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn entry(label: &str, stage: &str, micros: u64) -> Entry {
+        Entry {
+            label: label.to_string(),
+            day: 5,
+            stage: stage.to_string(),
+            input: "data/day5.txt".to_string(),
+            median: Duration::from_micros(micros),
+            mean: Duration::from_micros(micros + 10),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "# benchmarks\n\
+                    abc123\t5\tparse\tdata/day5.txt\t100000\t110000\n\
+                    \n\
+                    def456\t5\tsolve1\tdata/day5.txt\t2000\t2500\n";
+        let history = History::parse(text).unwrap();
+        assert_eq!(
+            history.entries,
+            vec![entry("abc123", "parse", 100), {
+                let mut solve = entry("def456", "solve1", 2);
+                solve.mean = Duration::from_micros(2) + Duration::from_nanos(500);
+                solve
+            }]
+        );
+        assert_eq!(
+            entry("abc123", "parse", 100).to_string(),
+            "abc123\t5\tparse\tdata/day5.txt\t100000\t110000"
+        );
+
+        assert!(History::parse("abc123\t5\tparse\tdata/day5.txt\t100").is_err());
+        assert!(History::parse("abc123\tfive\tparse\tdata/day5.txt\t1\t1").is_err());
+        assert!(History::parse("abc123\t5\tparse\tdata/day5.txt\t1ms\t1").is_err());
+    }
+
+    #[test]
+    fn test_append() {
+        let path = env::temp_dir().join(format!("bench-history-{}.txt", process::id()));
+        let path = path.to_str().unwrap();
+        History::append(path, &[entry("abc123", "parse", 100)]).unwrap();
+        History::append(path, &[entry("def456", "parse", 120)]).unwrap();
+        let text = fs::read_to_string(path).unwrap();
+        assert!(text.starts_with(HEADER));
+        let history = History::parse(&text).unwrap();
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(History::load(path).unwrap().entries.len(), 2);
+        fs::remove_file(path).unwrap();
+
+        assert!(History::load(path).unwrap().entries.is_empty());
+        // a directory exists but cannot be read as a file
+        let err = History::load(env::temp_dir().to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("cannot read"), "{err}");
+    }
+
+    #[test]
+    fn test_compare() {
+        let history = History {
+            entries: vec![
+                entry("abc123", "parse", 100),
+                entry("abc123", "solve1", 20),
+                entry("def456", "parse", 200),
+            ],
+        };
+        let current = vec![
+            entry("ghi789", "parse", 115),
+            entry("ghi789", "solve1", 21),
+            entry("ghi789", "solve2", 30),
+        ];
+
+        let comparisons = compare(&history, &current, None);
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!(comparisons[0].baseline.label, "def456");
+        assert!(!comparisons[0].is_regression(DEFAULT_THRESHOLD));
+        assert!(!comparisons[1].is_regression(DEFAULT_THRESHOLD));
+
+        let comparisons = compare(&history, &current, Some("abc123"));
+        assert!(comparisons[0].is_regression(DEFAULT_THRESHOLD));
+        assert!(!comparisons[0].is_regression(20.0));
+        assert_eq!(
+            comparisons[0].to_string(),
+            "day5 parse data/day5.txt: 100.0µs (abc123) -> 115.0µs (+15.0%)"
+        );
+        assert!(compare(&history, &current, Some("unknown")).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    any::Any,
+    panic,
+    sync::{mpsc, Once},
+    thread,
+    time::Duration,
+};
+
+const THREAD_NAME: &str = "isolated-solver";
+
+#[derive(Debug, PartialEq)]
+pub enum Outcome<T> {
+    Finished(T),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Keeps the default panic hook for all threads but the isolated ones, their
+/// panics are reported by the runner instead.
+fn silence_isolated_panics() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().name() != Some(THREAD_NAME) {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+/// Runs `fun` on its own thread so that a panic does not take the caller
+/// down. When `timeout` elapses first, the thread is left running detached,
+/// it is not possible to stop it from the outside.
+pub fn run_isolated<T: Send + 'static>(
+    timeout: Option<Duration>,
+    fun: impl FnOnce() -> T + Send + 'static,
+) -> Outcome<T> {
+    silence_isolated_panics();
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name(THREAD_NAME.to_string())
+        .spawn(move || {
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(fun));
+            _ = sender.send(result);
+        });
+    if let Err(err) = spawned {
+        return Outcome::Panicked(format!("cannot spawn solver thread: {err}"));
+    }
+
+    let result = match timeout {
+        Some(timeout) => match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => return Outcome::TimedOut(timeout),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Outcome::Panicked("solver thread exited early".to_string())
+            }
+        },
+        None => match receiver.recv() {
+            Ok(result) => result,
+            Err(_) => return Outcome::Panicked("solver thread exited early".to_string()),
+        },
+    };
+    match result {
+        Ok(value) => Outcome::Finished(value),
+        Err(payload) => Outcome::Panicked(panic_message(payload)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finished() {
+        assert_eq!(run_isolated(None, || 6 * 7), Outcome::Finished(42));
+    }
+
+    #[test]
+    fn test_panicked() {
+        let outcome = run_isolated(None, || -> u32 { panic!("Unknown letter") });
+        assert_eq!(outcome, Outcome::Panicked("Unknown letter".to_string()));
+
+        let outcome = run_isolated(None, || -> u32 { panic!("Unknown {}", "operation") });
+        assert_eq!(outcome, Outcome::Panicked("Unknown operation".to_string()));
+    }
+
+    #[test]
+    fn test_timed_out() {
+        let timeout = Duration::from_millis(10);
+        let outcome = run_isolated(Some(timeout), || thread::sleep(Duration::from_secs(1)));
+        assert_eq!(outcome, Outcome::TimedOut(timeout));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cli;
 pub mod common;
 pub mod day1;
 pub mod day10;
@@ -11,3 +15,14 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod error;
+pub mod fixtures;
+pub mod history;
+pub mod isolation;
+pub mod memory;
+pub mod output;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod watch;
@@ -1,39 +1,476 @@
-use advent_of_code::day1;
-use advent_of_code::day10;
-use advent_of_code::day11;
-use advent_of_code::day12;
-use advent_of_code::day2;
-use advent_of_code::day3;
-use advent_of_code::day4;
-use advent_of_code::day5;
-use advent_of_code::day6;
-use advent_of_code::day7;
-use advent_of_code::day8;
-use advent_of_code::day9;
-
-type RunPart = fn(String) -> String;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
 
-fn main() {
-    let days: Vec<(&str, RunPart, RunPart)> = vec![
-        ("day1", day1::run_part1, day1::run_part2),
-        ("day2", day2::run_part1, day2::run_part2),
-        ("day3", day3::run_part1, day3::run_part2),
-        ("day4", day4::run_part1, day4::run_part2),
-        ("day5", day5::run_part1, day5::run_part2),
-        ("day6", day6::run_part1, day6::run_part2),
-        ("day7", day7::run_part1, day7::run_part2),
-        ("day8", day8::run_part1, day8::run_part2),
-        ("day9", day9::run_part1, day9::run_part2),
-        ("day10", day10::run_part1, day10::run_part2),
-        ("day11", day11::run_part1, day11::run_part2),
-        ("day12", day12::run_part1, day12::run_part2),
-    ];
-    for (name, part1, part2) in days {
-        let path = format!("data/{name}.txt");
+use advent_of_code::answers::{self, Answers, Status};
+use advent_of_code::bench::{self, format_duration, Stats};
+use advent_of_code::cli::{
+    self, BenchOptions, Command, Days, ReportOptions, RunOptions, VerifyOptions, WatchOptions,
+};
+use advent_of_code::common::read_input;
+use advent_of_code::error::{Result, SolveError};
+use advent_of_code::fixtures;
+use advent_of_code::history::{self, Entry, History};
+use advent_of_code::memory;
+use advent_of_code::output::{self, Format, Record};
+use advent_of_code::report::{self, DayReport};
+use advent_of_code::runner;
+use advent_of_code::scaffold;
+use advent_of_code::solution::{self, Puzzle};
+use advent_of_code::watch::{self, Snapshot};
+
+// counts only once enabled by `run --mem`
+#[global_allocator]
+static ALLOCATOR: memory::Counting = memory::Counting;
+
+fn select(options: &RunOptions) -> Vec<&'static dyn Puzzle> {
+    match options.days {
+        Days::All => solution::solutions(),
+        Days::Single(day) => match solution::find(day) {
+            Some(puzzle) => vec![puzzle],
+            None => usage_error(&format!("day {day} is not implemented yet")),
+        },
+    }
+}
+
+fn input_paths(day: u32, options: &RunOptions) -> Vec<String> {
+    match &options.input {
+        Some(input) => vec![input.clone()],
+        None if options.example => vec![fixtures::example_path(day)],
+        None => runner::input_paths(Path::new("data"), day),
+    }
+}
+
+fn select_parts(options: &RunOptions) -> Vec<u32> {
+    [1, 2]
+        .into_iter()
+        .filter(|part| options.part.is_none() || options.part == Some(*part))
+        .collect()
+}
+
+fn load_input(day: u32, path: &str) -> Result<Arc<str>> {
+    match read_input(path) {
+        Ok(input) => Ok(Arc::from(input)),
+        Err(err) => Err(SolveError::new(day, format!("cannot read {path}: {err}"))),
+    }
+}
+
+/// Solves `parts` of a day on `input`, read from `path`.
+fn solve_input(
+    puzzle: &'static dyn Puzzle,
+    path: &str,
+    input: &Result<Arc<str>>,
+    parts: &[u32],
+    timeout: Option<Duration>,
+) -> Vec<Record> {
+    let day = puzzle.day();
+    runner::solve_parts(puzzle, input, parts, timeout)
+        .into_iter()
+        .map(|result| {
+            let (answer, error) = match result.answer {
+                Ok(answer) => (Some(answer), None),
+                Err(err) => (None, Some(err)),
+            };
+            Record {
+                day,
+                part: result.part,
+                input: path.to_string(),
+                answer,
+                elapsed: result.elapsed,
+                error,
+                memory: result.memory,
+            }
+        })
+        .collect()
+}
+
+/// Figures about `input` shown by `run --stats`, `None` when the day has
+/// none. An input that cannot be parsed has none either, its error is already
+/// reported with the answers.
+fn input_stats(
+    puzzle: &'static dyn Puzzle,
+    path: &str,
+    input: &Result<Arc<str>>,
+) -> Option<String> {
+    let parsed = puzzle.parse_input(input.as_ref().ok()?).ok()?;
+    let stats = puzzle.input_stats(&parsed)?;
+    Some(format!(
+        "day{} statistics of {path}:\n{stats}",
+        puzzle.day()
+    ))
+}
+
+/// Solves the selected parts of a day on each of its inputs, together with
+/// the figures about each input when they are asked for.
+fn solve_day(puzzle: &'static dyn Puzzle, options: &RunOptions) -> (Vec<Record>, Vec<String>) {
+    let day = puzzle.day();
+    let (mut records, mut stats) = (Vec::new(), Vec::new());
+    for path in input_paths(day, options) {
+        // loaded once, the standard input cannot be read a second time
+        let input = load_input(day, &path);
+        records.extend(solve_input(
+            puzzle,
+            &path,
+            &input,
+            &select_parts(options),
+            options.timeout,
+        ));
+        if options.stats {
+            stats.extend(input_stats(puzzle, &path, &input));
+        }
+    }
+    (records, stats)
+}
+
+fn run(options: RunOptions, format: Format) {
+    if options.memory {
+        memory::enable();
+    }
+    let mut records = Vec::new();
+    let mut failed = false;
+    for puzzle in select(&options) {
+        let (day_records, stats) = solve_day(puzzle, &options);
+        failed |= day_records.iter().any(|record| record.error.is_some());
+        // text output is printed as soon as each day is solved, the structured
+        // formats need all the records to produce a valid document
+        match format {
+            Format::Text => print!("{}", output::render(&day_records, format)),
+            _ => records.extend(day_records),
+        }
+        stats.iter().for_each(|stats| print!("\n{stats}"));
+    }
+    if format != Format::Text {
+        print!("{}", output::render(&records, format));
+    }
+    if failed {
+        process::exit(1)
+    }
+}
+
+fn print_stats(name: &str, stage: &str, stats: &Stats) {
+    println!(
+        "{name:<6} {stage:<6} {:>10} {:>10} {:>10} {:>10}",
+        format_duration(stats.min),
+        format_duration(stats.median),
+        format_duration(stats.mean),
+        format_duration(stats.p95),
+    );
+}
+
+/// Times the parsing and each of `parts` on `input`, one result per stage.
+/// Nothing is timed when the input cannot be solved.
+fn bench_input(
+    puzzle: &'static dyn Puzzle,
+    input: Result<Arc<str>>,
+    parts: &[u32],
+    options: &BenchOptions,
+) -> Option<Vec<(String, Stats)>> {
+    let (warmup, iterations) = (options.warmup, options.iterations);
+    // a single isolated run first, so that a failing or hanging part is
+    // reported and skipped instead of bringing the benchmark down
+    let checks = runner::solve_parts(puzzle, &input, parts, options.run.timeout);
+    if let Some(Err(err)) = checks
+        .into_iter()
+        .map(|result| result.answer)
+        .find(|answer| answer.is_err())
+    {
+        eprintln!("error: {err}");
+        return None;
+    }
+    let input = input.expect("the input was solved without errors");
+    let parsed = puzzle
+        .parse_input(&input)
+        .expect("the input was parsed without errors");
+
+    let mut stages = vec![(
+        "parse".to_string(),
+        bench::measure(warmup, iterations, || _ = puzzle.parse_input(&input)),
+    )];
+    for &part in parts {
+        let stats = bench::measure(warmup, iterations, || _ = puzzle.solve(&parsed, part));
+        stages.push((format!("solve{part}"), stats));
+    }
+    Some(stages)
+}
+
+fn run_bench(options: BenchOptions) {
+    // loaded first so that a broken history is reported before benchmarking
+    let known = match History::load(&options.history) {
+        Ok(known) => known,
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1)
+        }
+    };
+    let (mut total_median, mut total_mean) = (Duration::ZERO, Duration::ZERO);
+    let label = options
+        .label
+        .clone()
+        .or_else(history::git_label)
+        .unwrap_or_else(|| "unknown".to_string());
+    let mut entries = Vec::new();
+    let mut growth = Vec::new();
+    let mut record = |day: u32, stage: &str, input: &str, stats: &Stats| {
+        entries.push(Entry {
+            label: label.clone(),
+            day,
+            stage: stage.to_string(),
+            input: input.to_string(),
+            median: stats.median,
+            mean: stats.mean,
+        })
+    };
+    println!(
+        "{:<6} {:<6} {:>10} {:>10} {:>10} {:>10}",
+        "day", "stage", "min", "median", "mean", "p95"
+    );
+    for puzzle in select(&options.run) {
+        let day = puzzle.day();
+        let paths = input_paths(day, &options.run);
+        for path in &paths {
+            // inputs are told apart only when there are several of them
+            let name = match paths.len() {
+                1 => format!("day{day}"),
+                _ => format!("day{day}/{}", output::input_label(path)),
+            };
+            let input = load_input(day, path);
+            let parts = select_parts(&options.run);
+            let Some(stages) = bench_input(puzzle, input.clone(), &parts, &options) else {
+                continue;
+            };
+            for (stage, stats) in &stages {
+                print_stats(&name, stage, stats);
+                record(day, stage, path, stats);
+                total_median += stats.median;
+                total_mean += stats.mean;
+            }
+
+            let Some(factor) = options.scale else {
+                continue;
+            };
+            let scaled = input
+                .ok()
+                .and_then(|input| puzzle.scale_input(&input, factor as usize));
+            let Some(scaled) = scaled else {
+                growth.push(format!("{name:<6} cannot be scaled"));
+                continue;
+            };
+            let scaled_name = format!("{name}x{factor}");
+            let scaled_path = format!("{path} x{factor}");
+            let scaled_stages = bench_input(puzzle, Ok(Arc::from(scaled)), &parts, &options);
+            for ((stage, stats), (_, scaled)) in stages.iter().zip(scaled_stages.iter().flatten()) {
+                print_stats(&scaled_name, stage, scaled);
+                record(day, stage, &scaled_path, scaled);
+                growth.push(format!(
+                    "{name:<6} {stage:<6} {:>10} -> {:>10}  x{:.0}",
+                    format_duration(stats.median),
+                    format_duration(scaled.median),
+                    scaled.median.as_secs_f64() / stats.median.as_secs_f64().max(1e-9)
+                ));
+            }
+        }
+    }
+    println!(
+        "total: median {}, mean {}",
+        format_duration(total_median),
+        format_duration(total_mean)
+    );
+    if let Some(factor) = options.scale {
+        println!("median time on inputs {factor} times larger:");
+        growth.iter().for_each(|line| println!("{line}"));
+    }
+
+    if let Err(err) = History::append(&options.history, &entries) {
+        eprintln!("error: cannot write {}: {err}", options.history);
+        process::exit(1)
+    }
+    println!("recorded as '{label}' in {}", options.history);
+    if let Some(compare) = options.compare {
+        let comparisons = history::compare(&known, &entries, compare.baseline.as_deref());
+        let regressions = comparisons
+            .iter()
+            .filter(|comparison| comparison.is_regression(compare.threshold))
+            .collect::<Vec<_>>();
         println!(
-            "{name} result part1: {}, part2: {}",
-            part1(path.clone()),
-            part2(path)
-        )
+            "compared {} of {} stages: {} slower by more than {}%",
+            comparisons.len(),
+            entries.len(),
+            regressions.len(),
+            compare.threshold
+        );
+        regressions
+            .iter()
+            .for_each(|regression| println!("REGRESSION: {regression}"));
+        if !regressions.is_empty() {
+            process::exit(1)
+        }
+    }
+}
+
+fn verify(options: VerifyOptions) {
+    let known = load_answers(&options.answers);
+    let mut failed = false;
+    println!(
+        "{:<6} {:<6} {:<20} {:<16} details",
+        "day", "part", "input", "status"
+    );
+    for puzzle in select(&options.run) {
+        let day = puzzle.day();
+        for path in input_paths(day, &options.run) {
+            let input = load_input(day, &path);
+            let parts = select_parts(&options.run);
+            for result in runner::solve_parts(puzzle, &input, &parts, options.run.timeout) {
+                let part = result.part;
+                let (status, details) = match result.answer {
+                    Ok(actual) => match known.check(day, part, &path, &actual) {
+                        Status::Fail => (
+                            Status::Fail,
+                            format!(
+                                "expected '{}', got '{}'",
+                                answers::escape(known.get(day, part, &path).unwrap_or_default()),
+                                answers::escape(&actual.to_string())
+                            ),
+                        ),
+                        status => (status, String::new()),
+                    },
+                    Err(err) => (Status::Fail, err),
+                };
+                failed |= status == Status::Fail;
+                let line = format!(
+                    "{:<6} {:<6} {path:<20} {:<16} {details}",
+                    format!("day{day}"),
+                    format!("part{part}"),
+                    status.to_string(),
+                );
+                println!("{}", line.trim_end());
+            }
+        }
+    }
+    if failed {
+        process::exit(1)
+    }
+}
+
+fn load_answers(path: &str) -> Answers {
+    match Answers::load(path) {
+        Ok(known) => known,
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+/// Solves a day, then polls its inputs and the answers file and solves it
+/// again each time one of them changes.
+fn watch(options: WatchOptions) {
+    let puzzle = select(&options.run)[0];
+    let day = puzzle.day();
+    // the running binary cannot pick up a new version of the solver
+    let source = PathBuf::from(format!("src/day{day}.rs"));
+    let watched = || {
+        let mut paths = input_paths(day, &options.run)
+            .into_iter()
+            .map(PathBuf::from)
+            .collect::<Vec<PathBuf>>();
+        paths.push(PathBuf::from(&options.answers));
+        paths.push(source.clone());
+        paths
+    };
+
+    let mut previous = Vec::new();
+    let mut snapshot = Snapshot::take(&watched());
+    loop {
+        let known = load_answers(&options.answers);
+        let (records, _) = solve_day(puzzle, &options.run);
+        watch::diff(&previous, &records, &known)
+            .iter()
+            .for_each(|line| println!("{line}"));
+        previous = records;
+
+        loop {
+            thread::sleep(options.interval);
+            let current = Snapshot::take(&watched());
+            let changed = snapshot.changed(&current);
+            snapshot = current;
+            for path in &changed {
+                if *path == source {
+                    println!(
+                        "\n{} changed, rebuild and restart watch to use it",
+                        path.display()
+                    );
+                } else {
+                    println!("\n{} changed", path.display());
+                }
+            }
+            if changed.iter().any(|path| *path != source) {
+                break;
+            }
+        }
+    }
+}
+
+/// Solves each day on its main input and writes the Markdown report.
+fn report(options: ReportOptions) {
+    let known = load_answers(&options.answers);
+    let reports = select(&options.run)
+        .into_iter()
+        .map(|puzzle| {
+            let day = puzzle.day();
+            let path = input_paths(day, &options.run).swap_remove(0);
+            let input = load_input(day, &path);
+            let records = solve_input(puzzle, &path, &input, &[1, 2], options.run.timeout);
+            DayReport {
+                day,
+                name: puzzle.name().to_string(),
+                records,
+            }
+        })
+        .collect::<Vec<DayReport>>();
+    let document = report::render(&reports, &known);
+    match &options.output {
+        None => print!("{document}"),
+        Some(path) => match fs::write(path, document) {
+            Ok(()) => println!("wrote {path}"),
+            Err(err) => {
+                eprintln!("error: cannot write {path}: {err}");
+                process::exit(1)
+            }
+        },
+    }
+}
+
+fn new_day(day: u32) {
+    match scaffold::new_day(Path::new("."), day) {
+        Ok(created) => created
+            .iter()
+            .for_each(|path| println!("wrote {}", path.display())),
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1)
+        }
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {message}\n\n{}", cli::USAGE);
+    process::exit(2)
+}
+
+fn main() {
+    match cli::parse_args(env::args().skip(1)) {
+        Ok(Command::Run(options, format)) => run(options, format),
+        Ok(Command::Bench(options)) => run_bench(options),
+        Ok(Command::Verify(options)) => verify(options),
+        Ok(Command::Watch(options)) => watch(options),
+        Ok(Command::Report(options)) => report(options),
+        Ok(Command::New(day)) => new_day(day),
+        Ok(Command::Help) => println!("{}", cli::USAGE),
+        Err(err) => usage_error(&err.to_string()),
     }
 }
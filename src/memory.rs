@@ -0,0 +1,183 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+// signed, memory allocated before counting was enabled can be freed later
+static LIVE: AtomicI64 = AtomicI64::new(0);
+static PEAK: AtomicI64 = AtomicI64::new(0);
+
+/// Global allocator counting the allocations made through it, once
+/// [`enable`] was called. Install it in the binary with
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: memory::Counting = memory::Counting;
+/// ```
+pub struct Counting;
+
+fn record_alloc(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size as i64, Ordering::Relaxed) + size as i64;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    LIVE.fetch_sub(size as i64, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if ENABLED.load(Ordering::Relaxed) {
+            record_dealloc(layout.size());
+        }
+    }
+
+    /// A reallocation counts as a new allocation of `new_size` bytes that
+    /// replaces the old one.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && ENABLED.load(Ordering::Relaxed) {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Starts counting, there is no way back since memory allocated while
+/// counting has to be accounted for when it is freed.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Memory used by a piece of code.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemStats {
+    pub allocations: u64,
+    pub allocated: u64,
+    /// Highest number of bytes live at once, above what was live before.
+    pub peak: u64,
+    /// Bytes still live afterwards, e.g. the parsed input.
+    pub retained: u64,
+}
+
+impl MemStats {
+    /// Memory of running `self` and then `next` while keeping what `self`
+    /// retained, e.g. parsing the input and then solving a part from it.
+    pub fn then(&self, next: &MemStats) -> MemStats {
+        MemStats {
+            allocations: self.allocations + next.allocations,
+            allocated: self.allocated + next.allocated,
+            peak: self.peak.max(self.retained + next.peak),
+            retained: self.retained + next.retained,
+        }
+    }
+}
+
+/// Runs `fun` and reports the memory it used. The counters are global, so
+/// allocations made by other threads at the same time are included, and only
+/// one measurement can run at a time.
+pub fn measure<T>(fun: impl FnOnce() -> T) -> (T, MemStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+
+    let result = fun();
+    let stats = MemStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+        peak: (PEAK.load(Ordering::Relaxed) - live).max(0) as u64,
+        retained: (LIVE.load(Ordering::Relaxed) - live).max(0) as u64,
+    };
+    (result, stats)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_024 => format!("{bytes}B"),
+        1_024..1_048_576 => format!("{:.1}KiB", bytes as f64 / 1_024.0),
+        1_048_576..1_073_741_824 => format!("{:.1}MiB", bytes as f64 / 1_048_576.0),
+        _ => format!("{:.2}GiB", bytes as f64 / 1_073_741_824.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    #[test]
+    fn test_measure() {
+        enable();
+        // other tests run at the same time and may measure too, so only
+        // lower bounds of the counters that are never reset hold
+        let (buffer, stats) = measure(|| {
+            let scratch = vec![1u8; 8192];
+            vec![scratch[0]; 4096]
+        });
+        assert_eq!(buffer.len(), 4096);
+        assert!(stats.allocations >= 2);
+        assert!(stats.allocated >= 8192 + 4096);
+    }
+
+    #[test]
+    fn test_then() {
+        let parse = MemStats {
+            allocations: 3,
+            allocated: 300,
+            peak: 200,
+            retained: 100,
+        };
+        let solve = MemStats {
+            allocations: 2,
+            allocated: 150,
+            peak: 150,
+            retained: 0,
+        };
+        assert_eq!(
+            parse.then(&solve),
+            MemStats {
+                allocations: 5,
+                allocated: 450,
+                peak: 250,
+                retained: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1_536), "1.5KiB");
+        assert_eq!(format_bytes(8_000_000), "7.6MiB");
+    }
+}
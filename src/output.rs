@@ -0,0 +1,449 @@
+use std::{path::Path, time::Duration};
+
+use crate::answer::Answer;
+use crate::memory::{format_bytes, MemStats};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+/// Outcome of solving one part of a day on one input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+    pub answer: Option<Answer>,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+    pub memory: Option<MemStats>,
+}
+
+fn json_string(text: &str) -> String {
+    let mut result = String::from('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => result.push(ch),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn json_optional(text: &Option<String>) -> String {
+    text.as_deref().map_or("null".to_string(), json_string)
+}
+
+/// Numbers are kept as JSON numbers, text as strings and grids become arrays
+/// of rows. A part that failed or is not implemented has no answer, the
+/// `error` and `implemented` fields tell them apart.
+fn json_answer(answer: &Option<Answer>) -> String {
+    match answer {
+        Some(Answer::Int(number)) => number.to_string(),
+        Some(Answer::Text(text)) => json_string(text),
+        Some(Answer::Grid(rows)) => {
+            let rows = rows.iter().map(|row| json_string(row));
+            format!("[{}]", rows.collect::<Vec<String>>().join(", "))
+        }
+        Some(Answer::Unimplemented) | None => "null".to_string(),
+    }
+}
+
+/// Quotes a CSV field when needed, newlines are kept inside the quotes.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// The memory columns are only part of the output when memory was counted.
+fn has_memory(records: &[Record]) -> bool {
+    records.iter().any(|record| record.memory.is_some())
+}
+
+fn memory_fields(record: &Record) -> [Option<u64>; 3] {
+    match record.memory {
+        Some(memory) => [
+            Some(memory.allocations),
+            Some(memory.allocated),
+            Some(memory.peak),
+        ],
+        None => [None; 3],
+    }
+}
+
+/// One object per record. `answer` is a number, a string, an array of rows
+/// or `null`, and `implemented` is `false` only for the parts that are not
+/// implemented yet.
+fn render_json(records: &[Record]) -> String {
+    let with_memory = has_memory(records);
+    let lines = records
+        .iter()
+        .map(|record| {
+            let mut fields = format!(
+                "\"day\": {}, \"part\": {}, \"input\": {}, \"answer\": {}, \"implemented\": {}, \"elapsed_ns\": {}, \"error\": {}",
+                record.day,
+                record.part,
+                json_string(&record.input),
+                json_answer(&record.answer),
+                record.answer != Some(Answer::Unimplemented),
+                record.elapsed.as_nanos(),
+                json_optional(&record.error),
+            );
+            if with_memory {
+                let names = ["allocations", "allocated_bytes", "peak_bytes"];
+                for (name, value) in names.iter().zip(memory_fields(record)) {
+                    let value = value.map_or("null".to_string(), |value| value.to_string());
+                    fields.push_str(&format!(", \"{name}\": {value}"));
+                }
+            }
+            format!("  {{{fields}}}")
+        })
+        .collect::<Vec<String>>();
+    if lines.is_empty() {
+        "[]\n".to_string()
+    } else {
+        format!("[\n{}\n]\n", lines.join(",\n"))
+    }
+}
+
+/// Same columns as the JSON fields, the answer of a part that is not
+/// implemented is left empty with `false` in the `implemented` column.
+fn render_csv(records: &[Record]) -> String {
+    let with_memory = has_memory(records);
+    let mut result = String::from("day,part,input,answer,implemented,elapsed_ns,error");
+    if with_memory {
+        result.push_str(",allocations,allocated_bytes,peak_bytes");
+    }
+    result.push_str("\r\n");
+    for record in records {
+        result.push_str(&format!(
+            "{},{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            csv_field(&record.input),
+            csv_field(&match &record.answer {
+                Some(Answer::Unimplemented) | None => String::new(),
+                Some(answer) => answer.to_string(),
+            }),
+            record.answer != Some(Answer::Unimplemented),
+            record.elapsed.as_nanos(),
+            csv_field(record.error.as_deref().unwrap_or_default()),
+        ));
+        if with_memory {
+            for value in memory_fields(record) {
+                result.push_str(&format!(
+                    ",{}",
+                    value.map_or(String::new(), |v| v.to_string())
+                ));
+            }
+        }
+        result.push_str("\r\n");
+    }
+    result
+}
+
+fn text_value(record: &Record) -> String {
+    match (&record.answer, &record.error) {
+        (_, Some(error)) => format!("error: {error}"),
+        (Some(answer), None) => answer.to_string(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Renders the records the way the runner always printed them, one line per
+/// day listing the answers of its parts.
+fn render_lines(records: &[Record]) -> String {
+    let mut result = String::new();
+    for (idx, record) in records.iter().enumerate() {
+        let value = match &record.answer {
+            // a grid is drawn on lines of its own
+            Some(Answer::Grid(_)) if record.error.is_none() => {
+                format!("\n{}\n", text_value(record))
+            }
+            _ => text_value(record),
+        };
+        let same_day = |other: Option<&Record>| {
+            other.is_some_and(|other| other.day == record.day && other.input == record.input)
+        };
+        if idx > 0 && same_day(records.get(idx - 1)) {
+            result.push_str(&format!(", part{}: {value}", record.part));
+        } else {
+            result.push_str(&format!(
+                "day{} result part{}: {value}",
+                record.day, record.part
+            ));
+        }
+        if !same_day(records.get(idx + 1)) {
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// Name of an input in the header of a table, `data/day7/alice.txt` is
+/// `alice`.
+pub fn input_label(input: &str) -> String {
+    Path::new(input)
+        .file_stem()
+        .map_or(input.to_string(), |stem| stem.to_string_lossy().to_string())
+}
+
+/// Renders the records of a day solved on several inputs as a table with a
+/// column per input and a row per part. Multi-line answers, such as a CRT
+/// screen, are put next to each other line by line.
+fn render_side_by_side(records: &[Record]) -> String {
+    let mut inputs: Vec<&str> = Vec::new();
+    let mut parts: Vec<u32> = Vec::new();
+    for record in records {
+        if !inputs.contains(&record.input.as_str()) {
+            inputs.push(&record.input);
+        }
+        if !parts.contains(&record.part) {
+            parts.push(record.part);
+        }
+    }
+
+    let mut rows = vec![std::iter::once(format!("day{}", records[0].day))
+        .chain(inputs.iter().map(|input| input_label(input)))
+        .collect::<Vec<String>>()];
+    for part in parts {
+        let values = inputs.iter().map(|input| {
+            records
+                .iter()
+                .find(|record| record.part == part && record.input == *input)
+                .map_or(String::new(), text_value)
+        });
+        rows.push(
+            std::iter::once(format!("part{part}"))
+                .chain(values)
+                .collect(),
+        );
+    }
+
+    let widths = (0..=inputs.len())
+        .map(|col| {
+            rows.iter()
+                .flat_map(|row| row[col].split('\n'))
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<usize>>();
+    let mut result = String::new();
+    for row in rows {
+        let cells = row
+            .iter()
+            .map(|cell| cell.split('\n').collect::<Vec<&str>>())
+            .collect::<Vec<_>>();
+        let height = cells.iter().map(|lines| lines.len()).max().unwrap_or(1);
+        for idx in 0..height {
+            let line = cells
+                .iter()
+                .zip(&widths)
+                .map(|(lines, width)| format!("{:<width$}", lines.get(idx).unwrap_or(&"")))
+                .collect::<Vec<String>>()
+                .join("  ");
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// One indented line per part that had its memory counted.
+fn render_memory(records: &[Record], several_inputs: bool) -> String {
+    let mut result = String::new();
+    for record in records {
+        let Some(memory) = record.memory else {
+            continue;
+        };
+        let input = match several_inputs {
+            true => format!(" {}", input_label(&record.input)),
+            false => String::new(),
+        };
+        result.push_str(&format!(
+            "  part{}{input}: {} allocations, {} allocated, {} peak\n",
+            record.part,
+            memory.allocations,
+            format_bytes(memory.allocated),
+            format_bytes(memory.peak),
+        ));
+    }
+    result
+}
+
+/// Renders the days solved on a single input one line each, and the days
+/// solved on several inputs side by side.
+fn render_text(records: &[Record]) -> String {
+    let mut result = String::new();
+    for day_records in records.chunk_by(|a, b| a.day == b.day) {
+        let input = &day_records[0].input;
+        let several_inputs = day_records.iter().any(|record| record.input != *input);
+        if several_inputs {
+            result.push_str(&render_side_by_side(day_records));
+        } else {
+            result.push_str(&render_lines(day_records));
+        }
+        result.push_str(&render_memory(day_records, several_inputs));
+    }
+    result
+}
+
+pub fn render(records: &[Record], format: Format) -> String {
+    match format {
+        Format::Text => render_text(records),
+        Format::Json => render_json(records),
+        Format::Csv => render_csv(records),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 5,
+                part: 1,
+                input: "data/day5.txt".to_string(),
+                answer: Some(Answer::from("CMZ")),
+                elapsed: Duration::from_micros(15),
+                error: None,
+                memory: None,
+            },
+            Record {
+                day: 10,
+                part: 2,
+                input: "data/day10.txt".to_string(),
+                answer: Some(Answer::grid("\n#.\"#\",\n")),
+                elapsed: Duration::from_nanos(42),
+                error: None,
+                memory: None,
+            },
+            Record {
+                day: 10,
+                part: 1,
+                input: "data/day10.txt".to_string(),
+                answer: None,
+                elapsed: Duration::ZERO,
+                error: Some("bad input".to_string()),
+                memory: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            render(&get_test_records(), Format::Text),
+            "day5 result part1: CMZ\nday10 result part2: \n#.\"#\",\n, part1: error: bad input\n"
+        );
+    }
+
+    #[test]
+    fn test_render_side_by_side() {
+        let record = |input: &str, part, answer: &str| Record {
+            day: 10,
+            part,
+            input: input.to_string(),
+            answer: Some(Answer::parse(answer)),
+            elapsed: Duration::ZERO,
+            error: None,
+            memory: None,
+        };
+        let records = vec![
+            record("data/day10.txt", 1, "13140"),
+            record("data/day10.txt", 2, "#.\n.#"),
+            record("data/day10/alice.txt", 1, "15"),
+            record("data/day10/alice.txt", 2, "##\n##"),
+        ];
+        assert_eq!(
+            render(&records, Format::Text),
+            "day10  day10  alice\n\
+             part1  13140  15\n\
+             part2  #.     ##\n\
+             \x20      .#     ##\n"
+        );
+    }
+
+    #[test]
+    fn test_render_memory() {
+        let mut records = get_test_records();
+        records[0].memory = Some(MemStats {
+            allocations: 12,
+            allocated: 2048,
+            peak: 1536,
+            retained: 0,
+        });
+        assert_eq!(
+            render(&records[..1], Format::Text),
+            "day5 result part1: CMZ\n  part1: 12 allocations, 2.0KiB allocated, 1.5KiB peak\n"
+        );
+        assert!(render(&records[..1], Format::Json)
+            .contains("\"allocations\": 12, \"allocated_bytes\": 2048, \"peak_bytes\": 1536}"));
+        assert_eq!(
+            render(&records[..3], Format::Csv)
+                .lines()
+                .collect::<Vec<&str>>()[..2],
+            [
+                "day,part,input,answer,implemented,elapsed_ns,error,allocations,allocated_bytes,peak_bytes",
+                "5,1,data/day5.txt,CMZ,true,15000,,12,2048,1536",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        assert_eq!(
+            render(&get_test_records()[1..2], Format::Json),
+            "[\n  {\"day\": 10, \"part\": 2, \"input\": \"data/day10.txt\", \
+             \"answer\": [\"#.\\\"#\\\",\"], \"implemented\": true, \"elapsed_ns\": 42, \"error\": null}\n]\n"
+        );
+        assert_eq!(render(&[], Format::Json), "[]\n");
+
+        let mut records = get_test_records();
+        records[1].answer = Some(Answer::Unimplemented);
+        let json = render(&records, Format::Json);
+        assert!(json.contains("\"answer\": null, \"implemented\": false, \"elapsed_ns\": 42"));
+        // a part that failed has no answer either, but it is implemented
+        assert!(json.contains("\"answer\": null, \"implemented\": true, \"elapsed_ns\": 0"));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_eq!(
+            render(&get_test_records(), Format::Csv),
+            "day,part,input,answer,implemented,elapsed_ns,error\r\n\
+             5,1,data/day5.txt,CMZ,true,15000,\r\n\
+             10,2,data/day10.txt,\"#.\"\"#\"\",\",true,42,\r\n\
+             10,1,data/day10.txt,,true,0,bad input\r\n"
+        );
+
+        // an unimplemented part has no answer, unlike one answering the text
+        let mut records = get_test_records();
+        records[0].answer = Some(Answer::Unimplemented);
+        records[1].answer = Some(Answer::from("not implemented"));
+        let csv = render(&records[..2], Format::Csv);
+        let rows = csv.lines().skip(1).collect::<Vec<&str>>();
+        assert_eq!(
+            rows,
+            [
+                "5,1,data/day5.txt,,false,15000,",
+                "10,2,data/day10.txt,not implemented,true,42,"
+            ]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use crate::answer::Answer;
+use crate::answers::{Answers, Status};
+use crate::bench::format_duration;
+use crate::output::Record;
+
+/// Results of a day on a single input, one record per part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayReport {
+    pub day: u32,
+    pub name: String,
+    pub records: Vec<Record>,
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Markdown tables cannot hold fenced code blocks, so grid answers such as the
+/// CRT of day10 are put in an HTML code block on a single line.
+fn code_block(text: &str) -> String {
+    let lines = text
+        .trim_matches('\n')
+        .lines()
+        .map(|line| {
+            line.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('|', "&#124;")
+        })
+        .collect::<Vec<String>>();
+    format!("<pre>{}</pre>", lines.join("<br>"))
+}
+
+/// An inline code span holding `text` as it is. The fence is one backtick
+/// longer than the longest run of backticks in `text`, and a space is added
+/// inside the fence when a backtick or a space at either end would otherwise
+/// be read as part of it or dropped.
+fn code_span(text: &str) -> String {
+    let longest = text.split(|ch| ch != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    let padded = text.starts_with('`')
+        || text.ends_with('`')
+        || (text.starts_with(' ') && text.ends_with(' ') && !text.trim().is_empty());
+    let pad = if padded { " " } else { "" };
+    format!("{fence}{pad}{}{pad}{fence}", escape_cell(text))
+}
+
+fn answer_cell(record: &Record) -> String {
+    match (&record.answer, &record.error) {
+        (_, Some(error)) => format!("error: {}", escape_cell(error)),
+        (Some(Answer::Unimplemented), None) => "_not implemented_".to_string(),
+        (Some(answer @ Answer::Grid(_)), None) => code_block(&answer.to_string()),
+        (Some(answer), None) => code_span(&answer.to_string()),
+        (None, None) => String::new(),
+    }
+}
+
+fn status(record: &Record, known: &Answers) -> Status {
+    match &record.answer {
+        Some(answer) if record.error.is_none() => {
+            known.check(record.day, record.part, &record.input, answer)
+        }
+        _ => Status::Fail,
+    }
+}
+
+fn part_cell(report: &DayReport, part: u32) -> String {
+    report
+        .records
+        .iter()
+        .find(|record| record.part == part)
+        .map_or(String::new(), answer_cell)
+}
+
+/// Renders a Markdown document with one table row per day, showing the
+/// answers, how long each part took and how they compare to `known`.
+pub fn render(reports: &[DayReport], known: &Answers) -> String {
+    let mut result = String::from("# Advent of Code results\n\n");
+    result.push_str("| Day | Puzzle | Part 1 | Part 2 | Runtime | Status |\n");
+    result.push_str("| ---: | --- | --- | --- | ---: | --- |\n");
+    let mut statuses = Vec::new();
+    for report in reports {
+        let runtime = report
+            .records
+            .iter()
+            .map(|record| format_duration(record.elapsed))
+            .collect::<Vec<String>>()
+            .join(" / ");
+        let day_statuses = report
+            .records
+            .iter()
+            .map(|record| status(record, known))
+            .collect::<Vec<Status>>();
+        let status = day_statuses
+            .iter()
+            .map(|status| status.to_string())
+            .collect::<Vec<String>>()
+            .join(" / ");
+        result.push_str(&format!(
+            "| {} | {} | {} | {} | {runtime} | {status} |\n",
+            report.day,
+            escape_cell(&report.name),
+            part_cell(report, 1),
+            part_cell(report, 2),
+        ));
+        statuses.extend(day_statuses);
+    }
+
+    let count = |status: Status| statuses.iter().filter(|other| **other == status).count();
+    result.push_str(&format!(
+        "\n{} of {} parts verified, {} not implemented.\n",
+        count(Status::Pass),
+        statuses.len(),
+        count(Status::NotImplemented),
+    ));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn record(day: u32, part: u32, answer: &str, millis: u64) -> Record {
+        Record {
+            day,
+            part,
+            input: format!("data/day{day}.txt"),
+            answer: Some(Answer::parse(answer)),
+            elapsed: Duration::from_millis(millis),
+            error: None,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut known = Answers::default();
+        known.insert(10, 1, "data/day10.txt", "13140");
+        known.insert(10, 2, "data/day10.txt", "\n##..\n#..#\n");
+        known.insert(11, 1, "data/day11.txt", "10605");
+        let reports = vec![
+            DayReport {
+                day: 10,
+                name: "Cathode-Ray Tube".to_string(),
+                records: vec![
+                    record(10, 1, "13140", 1),
+                    record(10, 2, "\n##..\n#..#\n", 2),
+                ],
+            },
+            DayReport {
+                day: 11,
+                name: "Monkey in the Middle".to_string(),
+                records: vec![
+                    record(11, 1, "10605", 3),
+                    record(11, 2, "not implemented", 0),
+                ],
+            },
+        ];
+        assert_eq!(
+            render(&reports, &known),
+            "# Advent of Code results\n\n\
+             | Day | Puzzle | Part 1 | Part 2 | Runtime | Status |\n\
+             | ---: | --- | --- | --- | ---: | --- |\n\
+             | 10 | Cathode-Ray Tube | `13140` | <pre>##..<br>#..#</pre> | 1.00ms / 2.00ms | PASS / PASS |\n\
+             | 11 | Monkey in the Middle | `10605` | _not implemented_ | 3.00ms / 0ns | PASS / NOT-IMPLEMENTED |\n\
+             \n3 of 4 parts verified, 1 not implemented.\n"
+        );
+    }
+
+    #[test]
+    fn test_answer_cell() {
+        let mut failed = record(5, 1, "", 1);
+        failed.answer = None;
+        failed.error = Some("day5, line 3: a|b".to_string());
+        assert_eq!(answer_cell(&failed), "error: day5, line 3: a\\|b");
+        assert_eq!(code_block("<#|.>\n"), "<pre>&lt;#&#124;.&gt;</pre>");
+
+        assert_eq!(answer_cell(&record(1, 1, "a`b", 1)), "``a`b``");
+        assert_eq!(answer_cell(&record(1, 1, "`a``", 1)), "``` `a`` ```");
+        assert_eq!(answer_cell(&record(1, 1, "x|y", 1)), "`x\\|y`");
+        assert_eq!(code_span(" a "), "`  a  `");
+        assert_eq!(code_span("   "), "`   `");
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    fs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::answer::Answer;
+use crate::bench::format_duration;
+use crate::error::Result;
+use crate::isolation::{self, Outcome};
+use crate::memory::{self, MemStats};
+use crate::solution::Puzzle;
+
+/// Answer of one part of a day, or the error that prevented it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartResult {
+    pub part: u32,
+    pub answer: std::result::Result<Answer, String>,
+    /// Time spent parsing the input plus solving this part.
+    pub elapsed: Duration,
+    /// Memory used by parsing and solving, when it is counted.
+    pub memory: Option<MemStats>,
+}
+
+/// Value computed by a stage with the time and memory it took.
+type Measured<T> = (T, Duration, Option<MemStats>);
+
+/// Inputs of `day` found in `data_dir`: `day<N>.txt` followed by the `.txt`
+/// files of the `day<N>/` directory, e.g. the inputs of other accounts. When
+/// there is none, `day<N>.txt` is still returned so that it gets reported as
+/// missing.
+pub fn input_paths(data_dir: &Path, day: u32) -> Vec<String> {
+    let single = data_dir.join(format!("day{day}.txt"));
+    let mut paths = Vec::new();
+    if single.is_file() {
+        paths.push(single.display().to_string());
+    }
+    if let Ok(entries) = fs::read_dir(data_dir.join(format!("day{day}"))) {
+        let mut inputs = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+            .map(|path| path.display().to_string())
+            .collect::<Vec<String>>();
+        inputs.sort();
+        paths.extend(inputs);
+    }
+    if paths.is_empty() {
+        paths.push(single.display().to_string());
+    }
+    paths
+}
+
+/// Runs `fun` isolated and times it, a panic or a timeout is reported as an
+/// error of `stage`. Its memory is measured too when it is counted.
+fn run_stage<T: Send + 'static>(
+    stage: String,
+    timeout: Option<Duration>,
+    fun: impl FnOnce() -> Result<T> + Send + 'static,
+) -> std::result::Result<Measured<T>, String> {
+    let outcome = isolation::run_isolated(timeout, move || {
+        let start = Instant::now();
+        let (result, memory) = if memory::is_enabled() {
+            let (result, stats) = memory::measure(fun);
+            (result, Some(stats))
+        } else {
+            (fun(), None)
+        };
+        result.map(|value| (value, start.elapsed(), memory))
+    });
+    match outcome {
+        Outcome::Finished(result) => result.map_err(|err| err.to_string()),
+        Outcome::Panicked(message) => Err(format!("FAILED: {stage} panicked: {message}")),
+        Outcome::TimedOut(timeout) => Err(format!(
+            "TIMEOUT: {stage} gave no answer within {}",
+            format_duration(timeout)
+        )),
+    }
+}
+
+/// Parses the input once and solves the given parts from it. Parsing and
+/// each part run on their own thread, so that a panic or a solver that never
+/// finishes is reported as an error and the other days still get to run.
+pub fn solve_parts(
+    puzzle: &'static dyn Puzzle,
+    input: &Result<Arc<str>>,
+    parts: &[u32],
+    timeout: Option<Duration>,
+) -> Vec<PartResult> {
+    let day = puzzle.day();
+    let parsed = input
+        .clone()
+        .map_err(|err| err.to_string())
+        .and_then(|input| {
+            run_stage(format!("day{day} parse"), timeout, move || {
+                puzzle.parse_input(&input)
+            })
+        });
+    parts
+        .iter()
+        .map(|&part| {
+            let (answer, elapsed, memory) = match &parsed {
+                Ok((parsed, parse_elapsed, parse_memory)) => {
+                    let parsed = parsed.clone();
+                    let stage = format!("day{day} part{part}");
+                    match run_stage(stage, timeout, move || puzzle.solve(&parsed, part)) {
+                        Ok((answer, elapsed, memory)) => (
+                            Ok(answer),
+                            *parse_elapsed + elapsed,
+                            parse_memory
+                                .zip(memory)
+                                .map(|(parse, solve)| parse.then(&solve)),
+                        ),
+                        Err(err) => (Err(err), *parse_elapsed, None),
+                    }
+                }
+                Err(err) => (Err(err.clone()), Duration::ZERO, None),
+            };
+            PartResult {
+                part,
+                answer,
+                elapsed,
+                memory,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SolveError;
+    use crate::solution;
+
+    #[test]
+    fn test_solve_parts() {
+        let puzzle = solution::find(1).unwrap();
+        let input = Ok(Arc::from("1000\n2000\n\n4000\n\n500\n"));
+        let results = solve_parts(puzzle, &input, &[2, 1], None);
+        assert_eq!(results[0].part, 2);
+        assert_eq!(results[0].answer, Ok(Answer::Int(7500)));
+        assert_eq!(results[1].answer, Ok(Answer::Int(4000)));
+    }
+
+    #[test]
+    fn test_input_paths() {
+        let data = std::env::temp_dir().join(format!("inputs-{}", std::process::id()));
+        fs::create_dir_all(data.join("day7")).unwrap();
+        let path = |name: &str| data.join(name).display().to_string();
+        assert_eq!(input_paths(&data, 7), vec![path("day7.txt")]);
+
+        for name in ["day7/bob.txt", "day7/alice.txt", "day7/notes.md"] {
+            fs::write(data.join(name), "").unwrap();
+        }
+        assert_eq!(
+            input_paths(&data, 7),
+            vec![path("day7/alice.txt"), path("day7/bob.txt")]
+        );
+        fs::write(data.join("day7.txt"), "").unwrap();
+        assert_eq!(input_paths(&data, 7)[0], path("day7.txt"));
+        assert_eq!(input_paths(&data, 7).len(), 3);
+        fs::remove_dir_all(data).unwrap();
+    }
+
+    #[test]
+    fn test_errors() {
+        let puzzle = solution::find(1).unwrap();
+        let input = Ok(Arc::from("1000\nlots\n"));
+        let results = solve_parts(puzzle, &input, &[1, 2], None);
+        for result in results {
+            assert_eq!(
+                result.answer,
+                Err("day1, line 2: invalid number of calories 'lots'".to_string())
+            );
+        }
+
+        let input = Err(SolveError::new(1, "cannot read data/missing.txt"));
+        let results = solve_parts(puzzle, &input, &[1], None);
+        assert_eq!(
+            results[0].answer,
+            Err("day1: cannot read data/missing.txt".to_string())
+        );
+    }
+}
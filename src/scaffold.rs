@@ -0,0 +1,207 @@
+use std::{
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::fixtures;
+
+#[derive(Debug, PartialEq)]
+pub struct ScaffoldError(String);
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cannot create the day: {}", self.0)
+    }
+}
+
+impl Error for ScaffoldError {}
+
+const TEMPLATE: &str = r#"use crate::answer::Answer;
+use crate::common::{lines, read_file};
+use crate::error::Result;
+use crate::solution::Solution;
+
+const DAY: u32 = {day};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    type Input = Vec<String>;
+
+    const DAY: u32 = DAY;
+    const NAME: &'static str = "Day {day}";
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        Ok(lines(input).into_iter().map(String::from).collect())
+    }
+
+    fn part1(&self, _: &Vec<String>) -> Result<Answer> {
+        Ok(Answer::Unimplemented)
+    }
+
+    fn part2(&self, _: &Vec<String>) -> Result<Answer> {
+        Ok(Answer::Unimplemented)
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<Answer> {
+    Day{day}.part1(&Day{day}.parse(input)?)
+}
+
+pub fn solve_part2(input: &str) -> Result<Answer> {
+    Day{day}.part2(&Day{day}.parse(input)?)
+}
+
+pub fn run_part1(path: String) -> Result<Answer> {
+    solve_part1(&read_file(DAY, path)?)
+}
+
+pub fn run_part2(path: String) -> Result<Answer> {
+    solve_part2(&read_file(DAY, path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::read_example;
+
+    #[test]
+    fn test_example() {
+        let example = read_example("day{day}");
+        assert_eq!(solve_part1(&example), Ok(Answer::Unimplemented));
+        assert_eq!(solve_part2(&example), Ok(Answer::Unimplemented));
+    }
+}
+"#;
+
+fn io_error(path: &Path, err: impl fmt::Display) -> ScaffoldError {
+    ScaffoldError(format!("{}: {err}", path.display()))
+}
+
+/// Adds `pub mod day<day>;` to the `pub mod` list of `lib`, keeping it sorted
+/// the way rustfmt does.
+fn add_module(lib: &str, day: u32) -> String {
+    let module = format!("pub mod day{day};");
+    let mut lines = lib.lines().map(String::from).collect::<Vec<String>>();
+    let end = lines
+        .iter()
+        .rposition(|line| line.starts_with("pub mod "))
+        .map_or(lines.len(), |idx| idx + 1);
+    let start = lines[..end]
+        .iter()
+        .rposition(|line| !line.starts_with("pub mod "))
+        .map_or(0, |idx| idx + 1);
+    lines.insert(end, module);
+    lines[start..=end].sort_by_key(|line| line.trim_end_matches(';').to_string());
+    lines.join("\n") + "\n"
+}
+
+/// Writes `content` to `path` unless the file already exists, e.g. an input
+/// that was downloaded before the day was created.
+fn create_file(
+    path: &Path,
+    content: &str,
+    created: &mut Vec<PathBuf>,
+) -> Result<(), ScaffoldError> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| io_error(dir, err))?;
+    }
+    fs::write(path, content).map_err(|err| io_error(path, err))?;
+    created.push(path.to_path_buf());
+    Ok(())
+}
+
+/// Creates the module of a new day below the repository `root`: the solution
+/// skeleton, its `mod` declaration, an empty input file and an empty example
+/// with answers to fill in. The registry of days is generated by the build
+/// script, so it picks the module up on its own. Existing days are never
+/// overwritten.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let lib_path = root.join("src/lib.rs");
+    let module_path = root.join(format!("src/day{day}.rs"));
+    let data_path = root.join(format!("data/day{day}.txt"));
+    let example_path = root.join(fixtures::example_path(day));
+    let answers_path = root.join(fixtures::ANSWERS);
+
+    let lib = fs::read_to_string(&lib_path).map_err(|err| io_error(&lib_path, err))?;
+    if module_path.exists() || lib.lines().any(|line| line == format!("pub mod day{day};")) {
+        return Err(ScaffoldError(format!("day {day} already exists")));
+    }
+
+    let mut created = Vec::new();
+    let module = TEMPLATE.replace("{day}", &day.to_string());
+    fs::write(&module_path, module).map_err(|err| io_error(&module_path, err))?;
+    created.push(module_path);
+    fs::write(&lib_path, add_module(&lib, day)).map_err(|err| io_error(&lib_path, err))?;
+    created.push(lib_path);
+    create_file(&data_path, "", &mut created)?;
+    create_file(&example_path, "", &mut created)?;
+
+    // the example answers are left empty, parts that are not implemented yet
+    // are skipped by the example tests
+    let mut answers = fs::read_to_string(&answers_path).unwrap_or_default();
+    if !answers.is_empty() && !answers.ends_with('\n') {
+        answers.push('\n');
+    }
+    for part in [1, 2] {
+        answers.push_str(&format!(
+            "{day}\t{part}\t{}\t\n",
+            fixtures::example_path(day)
+        ));
+    }
+    fs::write(&answers_path, answers).map_err(|err| io_error(&answers_path, err))?;
+    created.push(answers_path);
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_add_module() {
+        let lib = "pub mod common;\npub mod day1;\npub mod day2;\npub mod error;\n";
+        assert_eq!(
+            add_module(lib, 13),
+            "pub mod common;\npub mod day1;\npub mod day13;\npub mod day2;\npub mod error;\n"
+        );
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub mod day1;\npub mod error;\n").unwrap();
+
+        let created = new_day(&root, 2).unwrap();
+        assert_eq!(created.len(), 5);
+        let module = fs::read_to_string(root.join("src/day2.rs")).unwrap();
+        assert!(module.contains("pub struct Day2;"));
+        assert!(module.contains("const DAY: u32 = 2;"));
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "pub mod day1;\npub mod day2;\npub mod error;\n"
+        );
+        assert_eq!(fs::read_to_string(root.join("data/day2.txt")).unwrap(), "");
+        assert_eq!(
+            fs::read_to_string(root.join("fixtures/answers.txt")).unwrap(),
+            "2\t1\tfixtures/day2.txt\t\n2\t2\tfixtures/day2.txt\t\n"
+        );
+
+        fs::write(root.join("data/day2.txt"), "kept").unwrap();
+        assert_eq!(
+            new_day(&root, 2),
+            Err(ScaffoldError("day 2 already exists".to_string()))
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("data/day2.txt")).unwrap(),
+            "kept"
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
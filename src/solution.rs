@@ -0,0 +1,195 @@
+use std::{any::Any, sync::Arc};
+
+use crate::answer::Answer;
+use crate::error::{Result, SolveError};
+
+/// A day of the puzzle calendar. The input is parsed once and both parts are
+/// solved from the parsed value.
+///
+/// Every `src/dayN.rs` module is expected to define a unit struct `DayN`
+/// implementing this trait, the build script adds it to [`solutions`].
+pub trait Solution {
+    /// The puzzle input after parsing, shared by both parts.
+    type Input;
+
+    const DAY: u32;
+
+    /// Title of the puzzle, as shown on its page.
+    const NAME: &'static str;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
+
+    /// A valid input `factor` times the size of `input`, used to check that
+    /// the day runs in linear time. `None` when the input cannot be grown.
+    fn scale(&self, _input: &str, _factor: usize) -> Option<String> {
+        None
+    }
+
+    /// Figures about a parsed input beyond the answers, shown by
+    /// `run --stats`. `None` when the day has nothing to add.
+    fn stats(&self, _input: &Self::Input) -> Option<String> {
+        None
+    }
+}
+
+/// Parsed input of a [`Puzzle`], it can be shared between threads so that
+/// each part runs isolated on the same parse.
+pub type Parsed = Arc<dyn Any + Send + Sync>;
+
+/// Object safe view of a [`Solution`], used to iterate over all days.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u32;
+
+    fn name(&self) -> &'static str;
+
+    fn parse_input(&self, input: &str) -> Result<Parsed>;
+
+    /// Solves `part` of an input returned by [`Puzzle::parse_input`].
+    fn solve(&self, parsed: &Parsed, part: u32) -> Result<Answer>;
+
+    /// See [`Solution::scale`].
+    fn scale_input(&self, input: &str, factor: usize) -> Option<String>;
+
+    /// See [`Solution::stats`], `None` too when `parsed` comes from another
+    /// day.
+    fn input_stats(&self, parsed: &Parsed) -> Option<String>;
+}
+
+impl<S> Puzzle for S
+where
+    S: Solution + Sync,
+    S::Input: Send + Sync + 'static,
+{
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn name(&self) -> &'static str {
+        S::NAME
+    }
+
+    fn parse_input(&self, input: &str) -> Result<Parsed> {
+        Ok(Arc::new(self.parse(input)?))
+    }
+
+    fn solve(&self, parsed: &Parsed, part: u32) -> Result<Answer> {
+        let input = parsed
+            .downcast_ref::<S::Input>()
+            .ok_or_else(|| SolveError::new(S::DAY, "input was parsed by another day"))?;
+        match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => Err(SolveError::new(S::DAY, format!("there is no part {part}"))),
+        }
+    }
+
+    fn scale_input(&self, input: &str, factor: usize) -> Option<String> {
+        self.scale(input, factor)
+    }
+
+    fn input_stats(&self, parsed: &Parsed) -> Option<String> {
+        self.stats(parsed.downcast_ref::<S::Input>()?)
+    }
+}
+
+// generated by build.rs, defines `solutions()`
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+pub fn find(day: u32) -> Option<&'static dyn Puzzle> {
+    solutions().into_iter().find(|puzzle| puzzle.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let days = solutions()
+            .iter()
+            .map(|puzzle| puzzle.day())
+            .collect::<Vec<u32>>();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!((1..=12).all(|day| days.contains(&day)));
+        assert!(find(25).is_none());
+        assert_eq!(
+            find(10).map(|puzzle| puzzle.name()),
+            Some("Cathode-Ray Tube")
+        );
+    }
+
+    #[test]
+    fn test_parse_once() {
+        let puzzle = find(1).unwrap();
+        let parsed = puzzle.parse_input("1000\n2000\n\n4000\n\n500\n").unwrap();
+        assert_eq!(puzzle.solve(&parsed, 1), Ok(Answer::Int(4000)));
+        assert_eq!(puzzle.solve(&parsed, 2), Ok(Answer::Int(7500)));
+        assert_eq!(
+            puzzle.solve(&parsed, 3),
+            Err(SolveError::new(1, "there is no part 3"))
+        );
+
+        let err = find(2).unwrap().solve(&parsed, 1).unwrap_err();
+        assert_eq!(err.message, "input was parsed by another day");
+    }
+
+    #[test]
+    fn test_scaled_inputs_are_valid() {
+        for puzzle in solutions() {
+            let example = crate::fixtures::read_example(&format!("day{}", puzzle.day()));
+            let Some(scaled) = puzzle.scale_input(&example, 5) else {
+                continue;
+            };
+            assert!(scaled.len() > 2 * example.trim().len());
+            let parsed = puzzle.parse_input(&scaled).unwrap();
+            assert!(puzzle.solve(&parsed, 1).is_ok(), "day{}", puzzle.day());
+            assert!(puzzle.solve(&parsed, 2).is_ok(), "day{}", puzzle.day());
+        }
+        assert!(find(1).unwrap().scale_input("1\n", 3).is_some());
+        assert!(find(12).unwrap().scale_input("SE\n", 3).is_none());
+    }
+
+    /// The days that can grow their input take about `FACTOR` times longer
+    /// on an input `FACTOR` times larger, with room for timing noise. A
+    /// quadratic day would be hundreds of times slower.
+    #[test]
+    fn test_scaled_inputs_grow_linearly() {
+        const FACTOR: usize = 20;
+        for puzzle in solutions() {
+            let Ok(input) = crate::common::read_input(&format!("data/day{}.txt", puzzle.day()))
+            else {
+                continue;
+            };
+            let Some(scaled) = puzzle.scale_input(&input, FACTOR) else {
+                continue;
+            };
+            let time = |input: &str| {
+                let stats = crate::bench::measure(1, 5, || {
+                    let parsed = puzzle.parse_input(input).unwrap();
+                    _ = puzzle.solve(&parsed, 1);
+                    _ = puzzle.solve(&parsed, 2);
+                });
+                stats.min.as_secs_f64()
+            };
+            let growth = time(&scaled) / time(&input).max(1e-9);
+            assert!(
+                growth < (FACTOR * 4) as f64,
+                "day{} got {growth:.0} times slower on an input {FACTOR} times larger",
+                puzzle.day()
+            );
+        }
+    }
+
+    #[test]
+    fn test_input_stats() {
+        let day1 = find(1).unwrap();
+        let parsed = day1.parse_input("1000\n2000\n\n4000\n").unwrap();
+        let stats = day1.input_stats(&parsed).unwrap();
+        assert!(stats.starts_with("elves: 2, items: 3\n"));
+        assert_eq!(find(2).unwrap().input_stats(&parsed), None);
+    }
+}
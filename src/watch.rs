@@ -0,0 +1,163 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use crate::answers::{self, Answers};
+use crate::bench::format_duration;
+use crate::output::Record;
+
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification time and size of the watched files, `None` for the files
+/// that do not exist.
+#[derive(Debug, Default, PartialEq)]
+pub struct Snapshot(HashMap<PathBuf, Option<(SystemTime, u64)>>);
+
+impl Snapshot {
+    pub fn take(paths: &[PathBuf]) -> Snapshot {
+        let files = paths.iter().map(|path| {
+            let state = fs::metadata(path)
+                .ok()
+                .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+            (path.clone(), state)
+        });
+        Snapshot(files.collect())
+    }
+
+    /// Files that were created, modified or removed since `self`, sorted.
+    pub fn changed(&self, other: &Snapshot) -> Vec<PathBuf> {
+        let mut changed = other
+            .0
+            .iter()
+            .filter(|(path, state)| self.0.get(*path) != Some(state))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.0
+                    .keys()
+                    .filter(|path| !other.0.contains_key(*path))
+                    .cloned(),
+            )
+            .collect::<Vec<PathBuf>>();
+        changed.sort();
+        changed
+    }
+}
+
+fn describe_answer(record: &Record) -> String {
+    match (&record.answer, &record.error) {
+        (_, Some(error)) => format!("error: {error}"),
+        (Some(answer), None) => answers::escape(&answer.to_string()),
+        (None, None) => String::new(),
+    }
+}
+
+fn describe_time(previous: Option<&Record>, current: &Record) -> String {
+    match previous {
+        Some(previous) if !previous.elapsed.is_zero() => {
+            let change =
+                (current.elapsed.as_secs_f64() / previous.elapsed.as_secs_f64() - 1.0) * 100.0;
+            format!(
+                "{} -> {} ({change:+.1}%)",
+                format_duration(previous.elapsed),
+                format_duration(current.elapsed)
+            )
+        }
+        _ => format_duration(current.elapsed),
+    }
+}
+
+/// Compares a run of a day with the `previous` one, one line per part and
+/// input telling whether the answer changed, how it compares to the known
+/// answers and how long it took.
+pub fn diff(previous: &[Record], current: &[Record], known: &Answers) -> Vec<String> {
+    current
+        .iter()
+        .map(|record| {
+            let before = previous
+                .iter()
+                .find(|other| other.part == record.part && other.input == record.input);
+            let answer = describe_answer(record);
+            let answer = match before.map(describe_answer) {
+                None => answer,
+                Some(before) if before == answer => format!("{answer} (unchanged)"),
+                Some(before) => format!("{before} -> {answer}"),
+            };
+            let status = match &record.answer {
+                Some(actual) if record.error.is_none() => {
+                    known.check(record.day, record.part, &record.input, actual)
+                }
+                _ => answers::Status::Fail,
+            };
+            format!(
+                "day{} part{} {}: {answer} [{status}] in {}",
+                record.day,
+                record.part,
+                record.input,
+                describe_time(before, record)
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Answer;
+    use std::{env, process};
+
+    fn record(part: u32, answer: &str, millis: u64) -> Record {
+        Record {
+            day: 7,
+            part,
+            input: "data/day7.txt".to_string(),
+            answer: Some(Answer::parse(answer)),
+            elapsed: Duration::from_millis(millis),
+            error: None,
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let dir = env::temp_dir().join(format!("watch-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (input, answers) = (dir.join("day7.txt"), dir.join("answers.txt"));
+        fs::write(&input, "$ ls\n").unwrap();
+        let paths = vec![input.clone(), answers.clone()];
+
+        let before = Snapshot::take(&paths);
+        assert!(before.changed(&Snapshot::take(&paths)).is_empty());
+        fs::write(&input, "$ cd /\n$ ls\n").unwrap();
+        fs::write(&answers, "").unwrap();
+        let after = Snapshot::take(&paths);
+        assert_eq!(before.changed(&after), vec![answers, input]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut known = Answers::default();
+        known.insert(7, 1, "data/day7.txt", "95437");
+        known.insert(7, 2, "data/day7.txt", "24933642");
+
+        let first = vec![record(1, "95437", 10), record(2, "1", 20)];
+        assert_eq!(
+            diff(&[], &first, &known),
+            vec![
+                "day7 part1 data/day7.txt: 95437 [PASS] in 10.00ms",
+                "day7 part2 data/day7.txt: 1 [FAIL] in 20.00ms",
+            ]
+        );
+        let second = vec![record(1, "95437", 15), record(2, "24933642", 10)];
+        assert_eq!(
+            diff(&first, &second, &known),
+            vec![
+                "day7 part1 data/day7.txt: 95437 (unchanged) [PASS] in 10.00ms -> 15.00ms (+50.0%)",
+                "day7 part2 data/day7.txt: 1 -> 24933642 [PASS] in 20.00ms -> 10.00ms (-50.0%)",
+            ]
+        );
+    }
+}